@@ -0,0 +1,116 @@
+//! Local stand-in for the external AI oracle.
+//!
+//! Answers `AiMoveRequest` POSTs with a turn planned by the built-in AI, or
+//! with deliberately bad replies to exercise the service's fallback:
+//!
+//! ```text
+//! cargo run --example mock_ai_oracle -- [port] [builtin|illegal|error]
+//! ```
+//!
+//! Point the application's `ai_oracle_url` parameter at
+//! `http://localhost:<port>/` and add `localhost` to the committee's HTTP
+//! allow list.
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+};
+
+use blitz_tactics::{
+    ai::plan_turn, create_starter_deck, rng::MatchRng, AiMoveRequest, AiMoveResponse, GameAction,
+};
+use linera_sdk::base::Timestamp;
+
+#[derive(Clone, Copy)]
+enum Mode {
+    /// Plays the built-in AI's turn.
+    Builtin,
+    /// Replies with a move that is never legal.
+    Illegal,
+    /// Replies with a server error.
+    Error,
+}
+
+fn main() -> std::io::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let port = args.next().unwrap_or_else(|| "8090".to_string());
+    let mode = match args.next().as_deref() {
+        None | Some("builtin") => Mode::Builtin,
+        Some("illegal") => Mode::Illegal,
+        Some("error") => Mode::Error,
+        Some(other) => panic!("unknown mode {other}"),
+    };
+
+    let listener = TcpListener::bind(format!("127.0.0.1:{port}"))?;
+    println!("mock AI oracle listening on http://127.0.0.1:{port}/");
+    for stream in listener.incoming() {
+        if let Err(error) = handle(stream?, mode) {
+            eprintln!("request failed: {error}");
+        }
+    }
+    Ok(())
+}
+
+fn handle(mut stream: TcpStream, mode: Mode) -> std::io::Result<()> {
+    let body = read_body(&mut stream)?;
+    let (status, reply) = match mode {
+        Mode::Error => ("500 Internal Server Error", String::new()),
+        Mode::Illegal => (
+            "200 OK",
+            to_json(&AiMoveResponse {
+                actions: vec![GameAction::PlayCard { card_id: u32::MAX }],
+            }),
+        ),
+        Mode::Builtin => match serde_json::from_slice::<AiMoveRequest>(&body) {
+            Ok(request) => ("200 OK", to_json(&plan(request))),
+            Err(error) => ("400 Bad Request", error.to_string()),
+        },
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{reply}",
+        reply.len()
+    )
+}
+
+fn plan(request: AiMoveRequest) -> AiMoveResponse {
+    let seat = request.seat as usize;
+    let mut rng = MatchRng::from_seed(request.game.match_id);
+    let actions = plan_turn(
+        &request.game,
+        seat,
+        request.difficulty,
+        &mut rng,
+        Timestamp::from(0),
+        &create_starter_deck(),
+    );
+    AiMoveResponse { actions }
+}
+
+fn to_json(response: &AiMoveResponse) -> String {
+    serde_json::to_string(response).expect("responses serialize")
+}
+
+/// Reads one HTTP request and returns its body.
+fn read_body(stream: &mut TcpStream) -> std::io::Result<Vec<u8>> {
+    let mut reader = BufReader::new(stream);
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    Ok(body)
+}
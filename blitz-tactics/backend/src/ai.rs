@@ -0,0 +1,389 @@
+use crate::{
+    rng::MatchRng, rules::find_card, AiDifficulty, Card, GameAction, GamePhase, GameState,
+    MoveSuggestion, ScoreBreakdown,
+};
+use linera_sdk::base::{AccountOwner, Timestamp};
+
+/// Account the built-in AI plays as. Reserved owners can't sign blocks, so
+/// nobody can act for the bot except the contract itself.
+pub const BOT_OWNER: AccountOwner = AccountOwner::Reserved(1);
+
+/// Board evaluation weights.
+const HEALTH_WEIGHT: i64 = 10;
+const BOARD_WEIGHT: i64 = 3;
+const HAND_WEIGHT: i64 = 1;
+
+/// Upper bound on the actions an easy bot takes in one turn.
+const EASY_MAX_ACTIONS: usize = 12;
+/// Simulated actions the hard bot may spend searching one turn. Each is a
+/// board copy plus a rules-engine step, so this bounds the fuel a bot turn
+/// costs the block.
+pub const SEARCH_BUDGET: u32 = 2_000;
+/// Simulated actions set aside for greedily finishing the turn once the
+/// search budget is spent, so a bot turn never simulates more than
+/// `SEARCH_BUDGET + FINISH_BUDGET` actions.
+const FINISH_BUDGET: u32 = 500;
+/// Longest turn accepted from an external AI.
+pub const MAX_PLAN_ACTIONS: usize = 64;
+
+/// Scores the board from `seat`'s team's point of view; higher is better.
+pub fn evaluate(game: &GameState, seat: usize) -> i64 {
+    let team = game.players[seat].team;
+    if game.game_phase == GamePhase::Finished {
+        return match game.winner {
+            Some(winner) if winner == team => i64::MAX / 2,
+            Some(_) => i64::MIN / 2,
+            None => 0,
+        };
+    }
+    score_breakdown(game, seat).total
+}
+
+/// The weighted parts of a running match's score: own team minus everyone
+/// else still in the game.
+pub fn score_breakdown(game: &GameState, seat: usize) -> ScoreBreakdown {
+    let team = game.players[seat].team;
+    let mut breakdown = ScoreBreakdown {
+        health: 0,
+        board: 0,
+        hand: 0,
+        total: 0,
+    };
+    for player in game.players.iter().filter(|p| !p.eliminated) {
+        let sign = if player.team == team { 1 } else { -1 };
+        let board: i64 = player
+            .field
+            .iter()
+            .map(|c| (c.attack + c.defense) as i64)
+            .sum();
+        breakdown.health += sign * player.health.max(0) as i64 * HEALTH_WEIGHT;
+        breakdown.board += sign * board * BOARD_WEIGHT;
+        breakdown.hand += sign * player.hand.len() as i64 * HAND_WEIGHT;
+    }
+    breakdown.total = breakdown.health + breakdown.board + breakdown.hand;
+    breakdown
+}
+
+/// Recommends `seat`'s next action: the first step of the hard bot's plan
+/// for the turn, explained. Only runs on copies, so `game` is untouched.
+pub fn suggest_move(game: &GameState, seat: usize, cards: &[Card]) -> Option<MoveSuggestion> {
+    if game.active_seat as usize != seat {
+        return None;
+    }
+    let now = game.turn_timer;
+    let owner = game.players[seat].owner;
+    let action = Planner::new(now, cards)
+        .search_turn(game, seat)
+        .into_iter()
+        .next()?;
+
+    let mut after = game.clone();
+    after.apply_action(owner, &action, now, cards).ok()?;
+    let won =
+        after.game_phase == GamePhase::Finished && after.winner == Some(game.players[seat].team);
+    let breakdown = score_breakdown(&after, seat);
+    let score_change = breakdown.total - score_breakdown(game, seat).total;
+
+    let name = |id: u32| find_card(cards, id).map_or_else(|| format!("card {}", id), |c| c.name);
+    let mut explanation = match &action {
+        GameAction::PlayCard { card_id } => format!("Play {} to build your board.", name(*card_id)),
+        GameAction::InstantCounter {
+            card_id,
+            target_card,
+        } => format!("Counter {} with {}.", name(*target_card), name(*card_id)),
+        GameAction::AttackPlayer { attacker_id, .. } => {
+            format!("Attack your opponent directly with {}.", name(*attacker_id))
+        }
+        GameAction::AttackCreature {
+            attacker_id,
+            defender_id,
+            ..
+        } => format!("Attack {} with {}.", name(*defender_id), name(*attacker_id)),
+        GameAction::EndTurn => "Nothing else improves your position; end your turn.".to_string(),
+    };
+    if won {
+        explanation.push_str(" This wins the match!");
+    }
+
+    Some(MoveSuggestion {
+        action: action.into(),
+        explanation,
+        score_change,
+        breakdown,
+    })
+}
+
+/// Checks an externally supplied turn for `seat`: every action must be in
+/// `legal_actions` when it is taken, and the plan must end the turn or the
+/// match.
+pub fn validate_plan(
+    game: &GameState,
+    seat: usize,
+    actions: &[GameAction],
+    now: Timestamp,
+    cards: &[Card],
+) -> Result<(), String> {
+    if actions.len() > MAX_PLAN_ACTIONS {
+        return Err("Plan is too long".to_string());
+    }
+    let owner = game.players[seat].owner;
+    let mut sim = game.clone();
+    for action in actions {
+        if sim.active_seat as usize != seat || !sim.legal_actions(seat).contains(action) {
+            return Err(format!("Illegal action {:?}", action));
+        }
+        sim.apply_action(owner, action, now, cards)?;
+    }
+    if sim.game_phase != GamePhase::Finished && sim.active_seat as usize == seat {
+        return Err("Plan doesn't end the turn".to_string());
+    }
+    Ok(())
+}
+
+/// Plans a full turn for `seat` at the given difficulty, ending with
+/// `EndTurn` unless the match is won first.
+pub fn plan_turn(
+    game: &GameState,
+    seat: usize,
+    difficulty: AiDifficulty,
+    rng: &mut MatchRng,
+    now: Timestamp,
+    cards: &[Card],
+) -> Vec<GameAction> {
+    let mut planner = Planner::new(now, cards);
+    match difficulty {
+        AiDifficulty::Easy => planner.random_turn(game, seat, rng),
+        AiDifficulty::Medium => planner.greedy_turn(game, seat).0,
+        AiDifficulty::Hard => planner.search_turn(game, seat),
+    }
+}
+
+/// Simulates actions on copies of the board, counting each one so the
+/// hard bot's search stays within budget.
+struct Planner<'a> {
+    now: Timestamp,
+    cards: &'a [Card],
+    nodes: u32,
+    limit: u32,
+}
+
+impl<'a> Planner<'a> {
+    fn new(now: Timestamp, cards: &'a [Card]) -> Self {
+        Planner {
+            now,
+            cards,
+            nodes: 0,
+            limit: SEARCH_BUDGET,
+        }
+    }
+
+    /// Whether the simulated actions allowed so far are used up. Every
+    /// loop that simulates checks this, so planning stops right at the limit.
+    fn exhausted(&self) -> bool {
+        self.nodes >= self.limit
+    }
+
+    fn apply(&mut self, game: &mut GameState, seat: usize, action: &GameAction) -> bool {
+        self.nodes += 1;
+        let owner = game.players[seat].owner;
+        game.apply_action(owner, action, self.now, self.cards)
+            .is_ok()
+    }
+
+    /// Easy: uniformly random legal moves, each creature attacking at most
+    /// once, until it picks `EndTurn` or runs out of moves.
+    fn random_turn(
+        &mut self,
+        game: &GameState,
+        seat: usize,
+        rng: &mut MatchRng,
+    ) -> Vec<GameAction> {
+        let mut sim = game.clone();
+        let mut actions = vec![];
+
+        for _ in 0..EASY_MAX_ACTIONS {
+            let options = turn_options(&sim, seat);
+            if options.is_empty() {
+                return actions;
+            }
+            let action = options[rng.below(options.len() as u32) as usize].clone();
+            if !self.apply(&mut sim, seat, &action) {
+                continue;
+            }
+            actions.push(action.clone());
+            if action == GameAction::EndTurn || sim.game_phase == GamePhase::Finished {
+                return actions;
+            }
+        }
+        actions.push(GameAction::EndTurn);
+        actions
+    }
+
+    /// Medium: develops the board with whichever affordable card scores
+    /// best, then gives each creature that hasn't attacked yet one attack,
+    /// taking the best-scoring target only when it beats holding back. Ties
+    /// go to the first option in `GameState::legal_actions`, so the plan is fully
+    /// deterministic. Also returns the board after the plan.
+    fn greedy_turn(&mut self, game: &GameState, seat: usize) -> (Vec<GameAction>, GameState) {
+        let mut sim = game.clone();
+        let mut actions = vec![];
+
+        loop {
+            let plays: Vec<GameAction> = turn_options(&sim, seat)
+                .into_iter()
+                .filter(|action| matches!(action, GameAction::PlayCard { .. }))
+                .collect();
+            let Some((_, action)) = self.best_action(&sim, seat, plays, i64::MIN) else {
+                break;
+            };
+            self.apply(&mut sim, seat, &action);
+            actions.push(action);
+        }
+
+        // One attack for every creature still ready, copies included
+        let player = &sim.players[seat];
+        let attackers: Vec<u32> = (0..player.field.len())
+            .filter(|slot| !player.has_attacked(*slot))
+            .map(|slot| player.field[slot].id)
+            .collect();
+        for attacker in attackers {
+            if sim.game_phase == GamePhase::Finished {
+                return (actions, sim);
+            }
+            if self.exhausted() {
+                break;
+            }
+            let attacks: Vec<GameAction> = turn_options(&sim, seat)
+                .into_iter()
+                .filter(|action| attacker_of(action) == Some(attacker))
+                .collect();
+            let holding_back = evaluate(&sim, seat);
+            if let Some((_, action)) = self.best_action(&sim, seat, attacks, holding_back) {
+                self.apply(&mut sim, seat, &action);
+                actions.push(action);
+            }
+        }
+
+        if sim.game_phase != GamePhase::Finished {
+            self.apply(&mut sim, seat, &GameAction::EndTurn);
+            actions.push(GameAction::EndTurn);
+        }
+        (actions, sim)
+    }
+
+    /// Hard: chooses each action by looking one full round ahead. Every
+    /// option is followed by the greedy rest of this turn and greedy replies
+    /// from the other seats, and the resulting board is scored. Once
+    /// `SEARCH_BUDGET` simulated actions are spent, the rest of the turn is
+    /// played greedily within `FINISH_BUDGET`.
+    fn search_turn(&mut self, game: &GameState, seat: usize) -> Vec<GameAction> {
+        let mut sim = game.clone();
+        let mut actions = vec![];
+
+        while sim.game_phase != GamePhase::Finished {
+            if self.exhausted() {
+                self.limit = self.nodes + FINISH_BUDGET;
+                actions.extend(self.greedy_turn(&sim, seat).0);
+                return actions;
+            }
+
+            let mut best: Option<(i64, GameAction)> = None;
+            for action in turn_options(&sim, seat) {
+                if self.exhausted() {
+                    break;
+                }
+                let mut trial = sim.clone();
+                if !self.apply(&mut trial, seat, &action) {
+                    continue;
+                }
+                if action != GameAction::EndTurn {
+                    trial = self.greedy_turn(&trial, seat).1;
+                }
+                self.play_replies(&mut trial, seat);
+
+                let score = evaluate(&trial, seat);
+                if best
+                    .as_ref()
+                    .map_or(true, |(best_score, _)| score > *best_score)
+                {
+                    best = Some((score, action));
+                }
+            }
+
+            let Some((_, action)) = best else {
+                if self.exhausted() {
+                    continue;
+                }
+                return actions;
+            };
+            self.apply(&mut sim, seat, &action);
+            actions.push(action.clone());
+            if action == GameAction::EndTurn {
+                return actions;
+            }
+        }
+        actions
+    }
+
+    /// Plays greedy turns for every other seat until it's `seat`'s turn again.
+    fn play_replies(&mut self, game: &mut GameState, seat: usize) {
+        for _ in 1..game.players.len() {
+            if game.game_phase == GamePhase::Finished
+                || game.active_seat as usize == seat
+                || self.exhausted()
+            {
+                return;
+            }
+            let replying = game.active_seat as usize;
+            *game = self.greedy_turn(game, replying).1;
+        }
+    }
+
+    /// The highest-scoring option that beats `to_beat`, trying each on a copy
+    /// of the board. Pass `i64::MIN` to accept any legal option.
+    fn best_action(
+        &mut self,
+        game: &GameState,
+        seat: usize,
+        options: Vec<GameAction>,
+        to_beat: i64,
+    ) -> Option<(i64, GameAction)> {
+        let mut best: Option<(i64, GameAction)> = None;
+        for action in options {
+            if self.exhausted() {
+                break;
+            }
+            let mut trial = game.clone();
+            if !self.apply(&mut trial, seat, &action) {
+                continue;
+            }
+            let score = evaluate(&trial, seat);
+            let threshold = best.as_ref().map_or(to_beat, |(best_score, _)| *best_score);
+            if score > threshold {
+                best = Some((score, action));
+            }
+        }
+        best
+    }
+}
+
+/// Actions the bot considers on its own turn: everything legal except
+/// counters, which it holds.
+fn turn_options(game: &GameState, seat: usize) -> Vec<GameAction> {
+    if game.active_seat as usize != seat {
+        return vec![];
+    }
+    game.legal_actions(seat)
+        .into_iter()
+        .filter(|action| !matches!(action, GameAction::InstantCounter { .. }))
+        .collect()
+}
+
+fn attacker_of(action: &GameAction) -> Option<u32> {
+    match action {
+        GameAction::AttackPlayer { attacker_id, .. }
+        | GameAction::AttackCreature { attacker_id, .. } => Some(*attacker_id),
+        _ => None,
+    }
+}
+
@@ -1,7 +1,18 @@
 use async_trait::async_trait;
-use blitz_tactics::{GamePhase, GameState, Message, Operation, PlayerInGame};
+use blitz_tactics::{
+    ai::{plan_turn, validate_plan, BOT_OWNER},
+    draft::draft_offer,
+    rng::MatchRng,
+    rules::{find_card, multiplayer_seats},
+    schema::{MIGRATION_BATCH_SIZE, SCHEMA_VERSION},
+    sealed::generate_pool,
+    state::SWEEP_BATCH_SIZE,
+    AdminAction, AiDifficulty, Card, DeckFormat, DraftRun, DraftStatus, EndReason, GameAction,
+    GamePhase, GameState, InstantiationArgument, MatchMode, Message, Operation, Parameters,
+    PlayerInGame, SealedPool,
+};
 use linera_sdk::{
-    base::{AccountOwner, Amount, ChainId, Timestamp},
+    base::{AccountOwner, Amount, ChainId},
     Contract, ContractRuntime,
 };
 
@@ -23,16 +34,31 @@ impl With<ContractRuntime<Self>> for BlitzTacticsContract {
 #[async_trait]
 impl Contract for BlitzTacticsContract {
     type Message = Message;
-    type InstantiationArgument = ();
-    type Parameters = ();
+    type InstantiationArgument = InstantiationArgument;
+    type Parameters = Parameters;
 
-    async fn instantiate(&mut self, _argument: Self::InstantiationArgument) {
+    async fn instantiate(&mut self, argument: Self::InstantiationArgument) {
         self.state.initialize_card_database().await.expect("Failed to initialize cards");
         self.state.total_games_played.set(0);
         self.state.next_match_id.set(1);
+        self.state.next_series_id.set(1);
+        self.state.next_puzzle_id.set(1);
+        self.state.admins.set(argument.admins);
+        self.state.schema_version.set(SCHEMA_VERSION);
     }
 
     async fn execute_operation(&mut self, operation: Operation) -> Self::Response {
+        if let Some(owner) = self.runtime.authenticated_signer() {
+            assert!(!self.state.is_banned(&owner).await, "Player is banned");
+        }
+
+        // After an upgrade, every operation carries the migration forward
+        let admins = self.runtime.application_parameters().admins;
+        self.state
+            .migrate(MIGRATION_BATCH_SIZE, &admins)
+            .await
+            .expect("Failed to migrate state");
+
         match operation {
             Operation::CreatePlayerProfile => {
                 let owner = self
@@ -48,288 +74,297 @@ impl Contract for BlitzTacticsContract {
                 vec![]
             }
 
-            Operation::CreateMatch { opponent } => {
+            Operation::CreateMatch { opponent, series } => {
                 let player1 = self
                     .runtime
                     .authenticated_signer()
                     .expect("Missing signer");
 
-                // Create new match state
-                let match_state = GameState {
-                    match_id: 0,
-                    player1: PlayerInGame {
-                        owner: player1,
-                        health: 20,
-                        mana: 3,
-                        hand: vec![],
-                        deck: (1..=10).collect(),
-                        field: vec![],
-                        graveyard: vec![],
-                    },
-                    player2: PlayerInGame {
-                        owner: opponent,
-                        health: 20,
-                        mana: 3,
-                        hand: vec![],
-                        deck: (1..=10).collect(),
-                        field: vec![],
-                        graveyard: vec![],
-                    },
-                    current_turn: 1,
-                    turn_timer: self.runtime.system_time(),
-                    game_phase: GamePhase::WaitingForPlayers,
-                    winner: None,
-                };
+                // Check before storing anything, the series included
+                self.state
+                    .validate_new_match(&[player1, opponent])
+                    .await
+                    .expect("Invalid match");
+
+                // Every match is part of a series, Bo1 being a single game
+                let series_id = self
+                    .state
+                    .create_series(player1, opponent, series)
+                    .await
+                    .expect("Failed to create series");
+
+                self.start_game(
+                    Some(series_id),
+                    MatchMode::Duel,
+                    DeckFormat::Constructed,
+                    vec![(player1, 0), (opponent, 1)],
+                    None,
+                    false,
+                )
+                .await
+            }
 
-                // Store match (creates match ID and links both players)
-                let _match_id = self.state.create_match(player1, opponent, match_state).await.expect("Failed to create match");
+            Operation::CreateMultiplayerMatch { opponents, mode } => {
+                let creator = self
+                    .runtime
+                    .authenticated_signer()
+                    .expect("Missing signer");
 
-                // Send cross-chain message to both players
-                let match_chain = self.runtime.chain_id();
-                let message = Message::MatchCreated {
-                    match_chain,
-                    player1,
-                    player2: opponent,
-                };
+                let seats = multiplayer_seats(creator, &opponents, mode)
+                    .expect("Invalid multiplayer match");
 
-                vec![message]
+                self.start_game(None, mode, DeckFormat::Constructed, seats, None, false)
+                    .await
             }
 
-            Operation::PlayCard { card_id } => {
+            Operation::StartDraft => {
                 let owner = self
                     .runtime
                     .authenticated_signer()
                     .expect("Missing signer");
 
-                // Get current match for this player
-                if let Some(mut game) = self.state.get_match_for_player(&owner).await {
-                    // Validate it's the player's turn
-                    let is_player1 = game.player1.owner == owner;
-                    let current_turn_matches = (game.current_turn % 2 == 1 && is_player1)
-                        || (game.current_turn % 2 == 0 && !is_player1);
-
-                    if !current_turn_matches {
-                        return vec![];
-                    }
+                if let Some(run) = self.state.get_draft_run(&owner).await {
+                    assert!(!run.is_active(), "Draft already in progress");
+                }
 
-                    // Get card from database
-                    let cards = self.state.card_database.get();
-                    let card = cards.iter().find(|c| c.id == card_id).cloned();
-
-                    if let Some(card) = card {
-                        // Update game state
-                        let player = if is_player1 {
-                            &mut game.player1
-                        } else {
-                            &mut game.player2
-                        };
-
-                        // Check mana
-                        if player.mana >= card.cost {
-                            player.mana -= card.cost;
-                            player.field.push(card.clone());
-                            
-                            // Remove from hand (simplified)
-                            if let Some(idx) = player.hand.iter().position(|c| c.id == card_id) {
-                                player.hand.remove(idx);
-                            }
+                let offer = self.deal_draft_offer(&owner, 0).await;
+                self.state
+                    .update_draft_run(DraftRun::new(owner, offer))
+                    .await
+                    .expect("Failed to start draft");
 
-                            // Update shared match
-                            self.state.update_match(game).await.ok();
+                vec![]
+            }
 
-                            // Broadcast card played
-                            return vec![Message::CardPlayed {
-                                player: owner,
-                                card,
-                            }];
-                        }
-                    }
+            Operation::DraftPick { choice } => {
+                let owner = self
+                    .runtime
+                    .authenticated_signer()
+                    .expect("Missing signer");
+
+                let mut run = self
+                    .state
+                    .get_draft_run(&owner)
+                    .await
+                    .expect("No draft in progress");
+                run.pick(choice).expect("Invalid draft pick");
+
+                // Deal the next offer until the deck is complete
+                if run.status == DraftStatus::Drafting {
+                    run.offer = self.deal_draft_offer(&owner, run.deck.len()).await;
                 }
 
+                self.state
+                    .update_draft_run(run)
+                    .await
+                    .expect("Failed to save draft pick");
+
                 vec![]
             }
 
-            Operation::EndTurn => {
-                let owner = self
+            Operation::CreateDraftMatch { opponent } => {
+                let player1 = self
                     .runtime
                     .authenticated_signer()
                     .expect("Missing signer");
 
-                if let Some(mut game) = self.state.get_match_for_player(&owner).await {
-                    // Check if game is already finished
-                    if game.game_phase == GamePhase::Finished {
-                        return vec![];
-                    }
+                for owner in [player1, opponent] {
+                    let ready = self
+                        .state
+                        .get_draft_run(&owner)
+                        .await
+                        .is_some_and(|run| run.status == DraftStatus::Playing);
+                    assert!(ready, "Both players need a completed draft deck");
+                }
 
-                    let is_player1 = game.player1.owner == owner;
-                    
-                    // Validate it's actually the caller's turn
-                    let is_correct_turn = (game.current_turn % 2 == 1 && is_player1)
-                        || (game.current_turn % 2 == 0 && !is_player1);
-                    
-                    if !is_correct_turn {
-                        return vec![];
-                    }
+                self.start_game(
+                    None,
+                    MatchMode::Duel,
+                    DeckFormat::Draft,
+                    vec![(player1, 0), (opponent, 1)],
+                    None,
+                    false,
+                )
+                .await
+            }
 
-                    // Increment turn
-                    game.current_turn += 1;
-                    game.turn_timer = self.runtime.system_time();
+            Operation::OpenSealedPool => {
+                let owner = self
+                    .runtime
+                    .authenticated_signer()
+                    .expect("Missing signer");
 
-                    // Determine next player
-                    let next_player = if game.current_turn % 2 == 1 {
-                        game.player1.owner
-                    } else {
-                        game.player2.owner
-                    };
+                assert!(
+                    self.state.get_player_stats(&owner).await.is_some(),
+                    "Create a player profile first"
+                );
+                assert!(
+                    self.state.get_sealed_pool(&owner).await.is_none(),
+                    "Sealed pool already opened"
+                );
+
+                let mut rng = MatchRng::from_parts(&[
+                    owner.to_string().as_bytes(),
+                    b"sealed",
+                    self.runtime.chain_id().to_string().as_bytes(),
+                    &self.runtime.block_height().0.to_le_bytes(),
+                ]);
+                let cards = self.state.get_available_cards().await;
+                let pool = generate_pool(&cards, &mut rng);
+                self.state
+                    .update_sealed_pool(SealedPool::new(owner, pool))
+                    .await
+                    .expect("Failed to open sealed pool");
 
-                    // Update shared match
-                    self.state.update_match(game).await.ok();
+                vec![]
+            }
 
-                    // Broadcast turn ended
-                    return vec![Message::TurnEnded { next_player }];
-                }
+            Operation::BuildSealedDeck { cards } => {
+                let owner = self
+                    .runtime
+                    .authenticated_signer()
+                    .expect("Missing signer");
+
+                let mut pool = self
+                    .state
+                    .get_sealed_pool(&owner)
+                    .await
+                    .expect("No sealed pool opened");
+                pool.build_deck(cards).expect("Invalid sealed deck");
+
+                self.state
+                    .update_sealed_pool(pool)
+                    .await
+                    .expect("Failed to save sealed deck");
 
                 vec![]
             }
 
-            Operation::AttackPlayer { attacker_id } => {
+            Operation::JoinSealedQueue => {
                 let owner = self
                     .runtime
                     .authenticated_signer()
                     .expect("Missing signer");
 
-                if let Some(mut game) = self.state.get_match_for_player(&owner).await {
-                    // Check if game is already finished
-                    if game.game_phase == GamePhase::Finished {
-                        return vec![];
+                let ready = self
+                    .state
+                    .get_sealed_pool(&owner)
+                    .await
+                    .is_some_and(|pool| pool.has_deck());
+                assert!(ready, "Build a sealed deck before queueing");
+
+                let opponent = self
+                    .state
+                    .join_sealed_queue(owner)
+                    .await
+                    .expect("Failed to join sealed queue");
+
+                match opponent {
+                    Some(waiting) => {
+                        self.start_game(
+                            None,
+                            MatchMode::Duel,
+                            DeckFormat::Sealed,
+                            vec![(waiting, 0), (owner, 1)],
+                            None,
+                            false,
+                        )
+                        .await
                     }
+                    None => vec![],
+                }
+            }
+
+            Operation::PlayCard { match_id, card_id } => {
+                let owner = self
+                    .runtime
+                    .authenticated_signer()
+                    .expect("Missing signer");
 
-                    let is_player1 = game.player1.owner == owner;
-                    
-                    // Validate turn
-                    let is_correct_turn = (game.current_turn % 2 == 1 && is_player1)
-                        || (game.current_turn % 2 == 0 && !is_player1);
-                    
-                    if !is_correct_turn {
-                        return vec![];
+                // Get current match for this player
+                if let Some(mut game) = self.state.get_match_for_player(&owner, match_id).await {
+                    let cards = self.state.get_match_cards(&game).await;
+                    let first_event = game.events.len();
+
+                    if let Ok(card) = game.play_card(owner, card_id, &cards) {
+                        // Broadcast card played
+                        let mut messages = vec![Message::CardPlayed {
+                            player: owner,
+                            card,
+                        }];
+                        messages.extend(zone_messages(&game, first_event));
+                        return self.save_match(game, messages).await;
                     }
-                    
-                    // Find attacker
-                    let attacker_field = if is_player1 {
-                        &game.player1.field
-                    } else {
-                        &game.player2.field
-                    };
+                }
 
-                    if let Some(attacker) = attacker_field.iter().find(|c| c.id == attacker_id) {
-                        let damage = attacker.attack as i32;
-                        
-                        // Deal damage to opponent
-                        if is_player1 {
-                            game.player2.health -= damage;
-                            if game.player2.health <= 0 {
-                                game.winner = Some(1);
-                                game.game_phase = GamePhase::Finished;
-                            }
-                        } else {
-                            game.player1.health -= damage;
-                            if game.player1.health <= 0 {
-                                game.winner = Some(2);
-                                game.game_phase = GamePhase::Finished;
-                            }
-                        }
+                vec![]
+            }
 
-                        // Check if game ended
-                        if game.game_phase == GamePhase::Finished {
-                            let winner_owner = if game.winner == Some(1) {
-                                Some(game.player1.owner)
-                            } else {
-                                Some(game.player2.owner)
-                            };
-
-                            // Update stats
-                            if let Some(w) = winner_owner {
-                                self.state.update_player_win(&w).await.ok();
-                                let loser = if w == game.player1.owner {
-                                    game.player2.owner
-                                } else {
-                                    game.player1.owner
-                                };
-                                self.state.update_player_loss(&loser).await.ok();
-                            }
+            Operation::EndTurn { match_id } => {
+                let owner = self
+                    .runtime
+                    .authenticated_signer()
+                    .expect("Missing signer");
 
-                            // End the match
-                            let match_id = game.match_id;
-                            self.state.update_match(game.clone()).await.ok();
-                            self.state.end_match(match_id).await.ok();
-
-                            return vec![Message::GameFinished {
-                                winner: winner_owner,
-                                rewards: Amount::ZERO,
-                            }];
-                        } else {
-                            // Update shared match (only if game not finished)
-                            self.state.update_match(game.clone()).await.ok();
+                if let Some(mut game) = self.state.get_match_for_player(&owner, match_id).await {
+                    let cards = self.state.get_match_cards(&game).await;
+                    let first_event = game.events.len();
+                    let now = self.runtime.system_time();
+
+                    if let Ok(next_player) = game.end_turn(owner, now, &cards) {
+                        // Broadcast turn ended
+                        let mut messages = vec![Message::TurnEnded { next_player }];
+                        messages.extend(zone_messages(&game, first_event));
+                        if !game.bot_uses_oracle {
+                            messages.extend(self.play_bot_turns(&mut game, None).await);
                         }
+                        return self.save_match(game, messages).await;
                     }
                 }
 
                 vec![]
             }
 
-            Operation::AttackCreature {
+            Operation::AttackPlayer {
+                match_id,
                 attacker_id,
-                defender_id,
+                target,
             } => {
                 let owner = self
                     .runtime
                     .authenticated_signer()
                     .expect("Missing signer");
 
-                if let Some(mut game) = self.state.get_match_for_player(&owner).await {
-                    // Check if game is already finished
-                    if game.game_phase == GamePhase::Finished {
-                        return vec![];
-                    }
-
-                    let is_player1 = game.player1.owner == owner;
-                    
-                    // Validate turn
-                    let is_correct_turn = (game.current_turn % 2 == 1 && is_player1)
-                        || (game.current_turn % 2 == 0 && !is_player1);
-                    
-                    if !is_correct_turn {
-                        return vec![];
+                if let Some(mut game) = self.state.get_match_for_player(&owner, match_id).await {
+                    if game.attack_player(owner, attacker_id, target).is_ok() {
+                        return self.save_match(game, vec![]).await;
                     }
+                }
 
-                    // Combat logic (simplified)
-                    let attacker_field = if is_player1 {
-                        &mut game.player1.field
-                    } else {
-                        &mut game.player2.field
-                    };
-
-                    let defender_field = if is_player1 {
-                        &mut game.player2.field
-                    } else {
-                        &mut game.player1.field
-                    };
+                vec![]
+            }
 
-                    // Find creatures
-                    let attacker = attacker_field.iter().find(|c| c.id == attacker_id).cloned();
-                    let defender = defender_field.iter().find(|c| c.id == defender_id).cloned();
+            Operation::AttackCreature {
+                match_id,
+                attacker_id,
+                defender_id,
+                target,
+            } => {
+                let owner = self
+                    .runtime
+                    .authenticated_signer()
+                    .expect("Missing signer");
 
-                    if let (Some(atk), Some(def)) = (attacker, defender) {
-                        // Remove both if attack >= defense
-                        if atk.attack >= def.defense {
-                            defender_field.retain(|c| c.id != defender_id);
-                        }
-                        if def.attack >= atk.defense {
-                            attacker_field.retain(|c| c.id != attacker_id);
-                        }
+                if let Some(mut game) = self.state.get_match_for_player(&owner, match_id).await {
+                    let cards = self.state.get_match_cards(&game).await;
+                    let first_event = game.events.len();
 
-                        // Update shared match
-                        self.state.update_match(game).await.ok();
+                    if game
+                        .attack_creature(owner, attacker_id, defender_id, target, &cards)
+                        .is_ok()
+                    {
+                        let messages = zone_messages(&game, first_event);
+                        return self.save_match(game, messages).await;
                     }
                 }
 
@@ -337,6 +372,7 @@ impl Contract for BlitzTacticsContract {
             }
 
             Operation::InstantCounter {
+                match_id,
                 card_id,
                 target_card,
             } => {
@@ -345,25 +381,254 @@ impl Contract for BlitzTacticsContract {
                     .authenticated_signer()
                     .expect("Missing signer");
 
-                // Get card
-                let cards = self.state.card_database.get();
-                let counter_card = cards.iter().find(|c| c.id == card_id).cloned();
-
-                if let Some(card) = counter_card {
-                    // Broadcast counter activated
-                    return vec![Message::CounterActivated {
-                        player: owner,
-                        counter_card: card,
-                        target_card,
-                    }];
+                if let Some(mut game) = self.state.get_match_for_player(&owner, match_id).await {
+                    let cards = self.state.get_match_cards(&game).await;
+                    let first_event = game.events.len();
+
+                    if let Ok(card) = game.instant_counter(owner, card_id, target_card, &cards) {
+                        // Broadcast counter activated
+                        let mut messages = vec![Message::CounterActivated {
+                            player: owner,
+                            counter_card: card,
+                            target_card,
+                        }];
+                        messages.extend(zone_messages(&game, first_event));
+                        return self.save_match(game, messages).await;
+                    }
+                }
+
+                vec![]
+            }
+
+            Operation::CreateBotMatch {
+                difficulty,
+                use_oracle,
+            } => {
+                let player = self
+                    .runtime
+                    .authenticated_signer()
+                    .expect("Missing signer");
+                assert!(
+                    !use_oracle || self.has_ai_oracle(),
+                    "No AI oracle is configured"
+                );
+
+                self.start_game(
+                    None,
+                    MatchMode::Duel,
+                    DeckFormat::Constructed,
+                    vec![(player, 0), (BOT_OWNER, 1)],
+                    Some(difficulty),
+                    use_oracle,
+                )
+                .await
+            }
+
+            Operation::RequestAIMove { match_id, plan } => {
+                let owner = self
+                    .runtime
+                    .authenticated_signer()
+                    .expect("Missing signer");
+
+                // Bots move straight after the human's turn unless the match
+                // waits for the oracle, so this mostly serves those matches
+                if let Some(mut game) = self.state.get_match_for_player(&owner, match_id).await {
+                    assert!(
+                        plan.is_none() || game.bot_uses_oracle,
+                        "Only matches using the AI oracle take a plan"
+                    );
+                    let messages = self.play_bot_turns(&mut game, plan).await;
+                    if !messages.is_empty() {
+                        return self.save_match(game, messages).await;
+                    }
                 }
 
                 vec![]
             }
 
-            Operation::RequestAIMove => {
-                // AI oracle integration would go here
-                // For now, return empty
+            Operation::AddPuzzle {
+                name,
+                description,
+                start,
+                objective,
+            } => {
+                let admin = self.admin_signer();
+                let puzzle_id = self
+                    .state
+                    .add_puzzle(name, description, start, objective)
+                    .await
+                    .expect("Invalid puzzle");
+                self.audit(admin, AdminAction::AddPuzzle, format!("puzzle {}", puzzle_id));
+
+                vec![]
+            }
+
+            Operation::SolvePuzzle { puzzle_id, actions } => {
+                let owner = self
+                    .runtime
+                    .authenticated_signer()
+                    .expect("Missing signer");
+
+                let puzzle = self
+                    .state
+                    .get_puzzle(puzzle_id)
+                    .await
+                    .expect("Puzzle not found");
+                let cards = self.state.get_match_cards(&puzzle.start).await;
+                puzzle
+                    .verify(owner, &actions, self.runtime.system_time(), &cards)
+                    .expect("Puzzle not solved");
+
+                self.state
+                    .record_puzzle_solved(&owner, puzzle_id)
+                    .await
+                    .expect("Failed to record puzzle");
+
+                vec![]
+            }
+
+            Operation::MigrateState { batch_size } => {
+                let admin = self.admin_signer();
+                let admins = self.runtime.application_parameters().admins;
+                let migrated = self
+                    .state
+                    .migrate(batch_size, &admins)
+                    .await
+                    .expect("Failed to migrate state");
+                let detail = format!("{} records migrated", migrated);
+                self.audit(admin, AdminAction::MigrateState, detail);
+
+                vec![]
+            }
+
+            Operation::AddCard { card } => {
+                let admin = self.admin_signer();
+                let detail = format!("card {} ({})", card.id, card.name);
+                self.state.add_card(card).await.expect("Failed to add card");
+                self.audit(admin, AdminAction::AddCard, detail);
+
+                vec![]
+            }
+
+            Operation::UpdateCard { card } => {
+                let admin = self.admin_signer();
+                let detail = format!("card {} ({})", card.id, card.name);
+                self.state
+                    .update_card(card)
+                    .await
+                    .expect("Failed to update card");
+                self.audit(admin, AdminAction::UpdateCard, detail);
+
+                vec![]
+            }
+
+            Operation::RetireCard { card_id } => {
+                let admin = self.admin_signer();
+                self.state
+                    .retire_card(card_id)
+                    .await
+                    .expect("Failed to retire card");
+                self.audit(admin, AdminAction::RetireCard, format!("card {}", card_id));
+
+                vec![]
+            }
+
+            Operation::BanPlayer { player, reason } => {
+                let admin = self.admin_signer();
+                let detail = format!("{}: {}", player, reason);
+                self.state
+                    .ban_player(player, reason)
+                    .await
+                    .expect("Failed to ban player");
+                self.audit(admin, AdminAction::BanPlayer, detail);
+
+                vec![]
+            }
+
+            Operation::UnbanPlayer { player } => {
+                let admin = self.admin_signer();
+                self.state
+                    .unban_player(&player)
+                    .await
+                    .expect("Failed to unban player");
+                self.audit(admin, AdminAction::UnbanPlayer, player.to_string());
+
+                vec![]
+            }
+
+            Operation::ForceEndMatch { match_id, winner } => {
+                let admin = self.admin_signer();
+                let mut game = self
+                    .state
+                    .get_match_by_id(match_id)
+                    .await
+                    .expect("Match not found");
+                game.force_end(winner).expect("Failed to end match");
+
+                let detail = match winner {
+                    Some(team) => format!("match {}, team {} wins", match_id, team),
+                    None => format!("match {}, draw", match_id),
+                };
+                self.audit(admin, AdminAction::ForceEndMatch, detail);
+                self.save_match(game, vec![]).await
+            }
+
+            Operation::SweepAbandonedMatches => {
+                let now = self.runtime.system_time();
+                self.state.start_sweep(now).expect("Sweep not allowed yet");
+
+                let mut messages = vec![];
+                let games = self.state.find_abandoned_matches(now, SWEEP_BATCH_SIZE).await;
+                for mut game in games {
+                    let cards = self.state.get_match_cards(&game).await;
+                    let first_event = game.events.len();
+                    let Ok(next_player) = game.abandon(now, &cards) else {
+                        continue;
+                    };
+
+                    // A no-contest leaves stats untouched and closes its series
+                    if game.end_reason == Some(EndReason::NoContest) {
+                        if let Some(series_id) = game.series_id {
+                            if let Some(mut series) = self.state.get_series(series_id).await {
+                                series.no_contest = true;
+                                self.state.update_series(series).await.ok();
+                            }
+                        }
+                        let match_id = game.match_id;
+                        self.state.update_match(game).await.ok();
+                        self.state.end_match(match_id, now).await.ok();
+                        messages.push(Message::GameFinished {
+                            winners: vec![],
+                            rewards: Amount::ZERO,
+                        });
+                        continue;
+                    }
+
+                    let mut game_messages: Vec<Message> = next_player
+                        .map(|next_player| Message::TurnEnded { next_player })
+                        .into_iter()
+                        .collect();
+                    game_messages.extend(zone_messages(&game, first_event));
+                    if next_player.is_some() && !game.bot_uses_oracle {
+                        game_messages.extend(self.play_bot_turns(&mut game, None).await);
+                    }
+                    messages.extend(self.save_match(game, game_messages).await);
+                }
+                messages
+            }
+
+            Operation::RotateAdmins { admins } => {
+                let admin = self.admin_signer();
+                let detail = admins
+                    .iter()
+                    .map(|owner| owner.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                self.state
+                    .rotate_admins(admins)
+                    .expect("Failed to rotate admins");
+                self.audit(admin, AdminAction::RotateAdmins, detail);
+
                 vec![]
             }
         }
@@ -373,8 +638,7 @@ impl Contract for BlitzTacticsContract {
         match message {
             Message::MatchCreated {
                 match_chain,
-                player1,
-                player2,
+                players,
             } => {
                 // Store match reference on player chains
             }
@@ -387,7 +651,7 @@ impl Contract for BlitzTacticsContract {
                 // Notify next player
             }
 
-            Message::GameFinished { winner, rewards } => {
+            Message::GameFinished { winners, rewards } => {
                 // Stats are already updated in the operation, just increment total games
                 let total = self.state.total_games_played.get();
                 self.state.total_games_played.set(total + 1);
@@ -400,6 +664,15 @@ impl Contract for BlitzTacticsContract {
             } => {
                 // Handle counter logic
             }
+
+            Message::ZoneChanged {
+                player,
+                card_id,
+                from,
+                to,
+            } => {
+                // Update UI via event stream
+            }
         }
     }
 
@@ -407,3 +680,319 @@ impl Contract for BlitzTacticsContract {
         self.state.save().await.expect("Failed to save state");
     }
 }
+
+impl BlitzTacticsContract {
+    /// Starts a new game with `seats` as (owner, team) in seat order; the
+    /// first seat takes the first turn.
+    async fn start_game(
+        &mut self,
+        series_id: Option<u64>,
+        mode: MatchMode,
+        format: DeckFormat,
+        seats: Vec<(AccountOwner, u8)>,
+        bot_difficulty: Option<AiDifficulty>,
+        bot_uses_oracle: bool,
+    ) -> Vec<Message> {
+        // Create new match state
+        let mut players = Vec::new();
+        for (seat, (owner, team)) in seats.iter().enumerate() {
+            let deck = self.deck_for(owner, format).await;
+            players.push(PlayerInGame::new(*owner, seat as u8, *team, deck));
+        }
+        let match_id = *self.state.next_match_id.get();
+        let now = self.runtime.system_time();
+        let mut match_state = GameState::new(match_id, series_id, mode, format, players, now);
+        match_state.bot_difficulty = bot_difficulty;
+        match_state.bot_uses_oracle = bot_uses_oracle;
+
+        // Shuffle every deck, then deal opening hands
+        let mut rng = match_state.rng(self.runtime.chain_id(), self.runtime.block_height());
+        for seat in 0..match_state.players.len() {
+            match_state.shuffle_deck(seat, &mut rng);
+        }
+        let cards = self.state.get_match_cards(&match_state).await;
+        match_state.deal_opening_hands(&cards);
+        let mut opening_messages = zone_messages(&match_state, 0);
+
+        // A bot in the first seat opens straight away
+        if !bot_uses_oracle {
+            opening_messages.extend(self.play_bot_turns(&mut match_state, None).await);
+        }
+
+        // Store match (creates match ID and links every player)
+        let match_id = self
+            .state
+            .create_match(match_state)
+            .await
+            .expect("Invalid match");
+
+        if let Some(series_id) = series_id {
+            if let Some(mut series) = self.state.get_series(series_id).await {
+                series.match_ids.push(match_id);
+                self.state.update_series(series).await.ok();
+            }
+        }
+
+        // Send cross-chain message to every player
+        let match_chain = self.runtime.chain_id();
+        let message = Message::MatchCreated {
+            match_chain,
+            players: seats.into_iter().map(|(owner, _)| owner).collect(),
+        };
+
+        let mut messages = vec![message];
+        messages.extend(opening_messages);
+        messages
+    }
+
+    /// Stores a match after an action. If the action ended the game, closes
+    /// the match and settles it: multiplayer matches straight from the final
+    /// standings, duels through their series, which either starts the next
+    /// game or, once decided, updates player stats.
+    async fn save_match(&mut self, game: GameState, mut messages: Vec<Message>) -> Vec<Message> {
+        if game.game_phase != GamePhase::Finished {
+            // Update shared match
+            self.state.update_match(game).await.ok();
+            return messages;
+        }
+
+        let winners = game.winners();
+        let (standings, places) = game.standings();
+
+        // End the match
+        let match_id = game.match_id;
+        let series_id = game.series_id;
+        let format = game.format;
+        let bot_difficulty = game.bot_difficulty;
+        let bot_uses_oracle = game.bot_uses_oracle;
+        let pve = game.players.iter().any(|p| p.is_bot());
+        self.state.update_match(game).await.ok();
+        let now = self.runtime.system_time();
+        self.state.end_match(match_id, now).await.ok();
+
+        // Draft results count towards each player's run; draws don't count
+        if format == DeckFormat::Draft && !winners.is_empty() {
+            for (owner, _) in &standings {
+                self.record_draft_result(owner, winners.contains(owner)).await;
+            }
+        }
+
+        let Some(series_id) = series_id else {
+            // Update stats
+            for (owner, place) in standings {
+                if pve {
+                    let won = (!winners.is_empty()).then(|| winners.contains(&owner));
+                    self.state.update_player_pve(&owner, won).await.ok();
+                } else if winners.is_empty() {
+                    self.state.update_player_draw(&owner).await.ok();
+                } else {
+                    self.state
+                        .update_player_placement(&owner, place, places)
+                        .await
+                        .ok();
+                }
+            }
+
+            messages.push(Message::GameFinished {
+                winners,
+                rewards: Amount::ZERO,
+            });
+            return messages;
+        };
+
+        let Some(mut series) = self.state.get_series(series_id).await else {
+            return messages;
+        };
+        series.record_game(winners.first().copied());
+        self.state.update_series(series.clone()).await.ok();
+
+        // Start the next game until the series is decided
+        if !series.is_over() {
+            let (first, second) = series.next_seats();
+            let seats = vec![(first, 0), (second, 1)];
+            let next_game = self
+                .start_game(
+                    Some(series_id),
+                    MatchMode::Duel,
+                    DeckFormat::Constructed,
+                    seats,
+                    bot_difficulty,
+                    bot_uses_oracle,
+                )
+                .await;
+            messages.extend(next_game);
+            return messages;
+        }
+
+        // Only the series result counts towards player stats
+        let series_winner = series.winner();
+        if pve {
+            for owner in [series.player1, series.player2] {
+                let won = series_winner.map(|w| w == owner);
+                self.state.update_player_pve(&owner, won).await.ok();
+            }
+        } else if let Some(w) = series_winner {
+            self.state.update_player_win(&w).await.ok();
+            let loser = if w == series.player1 {
+                series.player2
+            } else {
+                series.player1
+            };
+            self.state.update_player_loss(&loser).await.ok();
+        } else {
+            self.state.update_player_draw(&series.player1).await.ok();
+            self.state.update_player_draw(&series.player2).await.ok();
+        }
+
+        messages.push(Message::GameFinished {
+            winners: series_winner.into_iter().collect(),
+            rewards: Amount::ZERO,
+        });
+        messages
+    }
+
+    /// Plays bot turns for as long as a bot holds the active seat. The first
+    /// follows `plan` when one is given.
+    async fn play_bot_turns(
+        &mut self,
+        game: &mut GameState,
+        mut plan: Option<Vec<GameAction>>,
+    ) -> Vec<Message> {
+        let cards = self.state.get_match_cards(game).await;
+        let mut messages = vec![];
+        for _ in 0..game.players.len() {
+            if game.game_phase == GamePhase::Finished
+                || !game.player(game.active_seat as usize).is_bot()
+            {
+                break;
+            }
+            messages.extend(self.play_bot_turn(game, &cards, plan.take()));
+        }
+        messages
+    }
+
+    /// Plays the active bot seat's turn, from `plan` or else the built-in
+    /// AI, returning the messages a human making the same moves would have
+    /// produced.
+    fn play_bot_turn(
+        &mut self,
+        game: &mut GameState,
+        cards: &[Card],
+        plan: Option<Vec<GameAction>>,
+    ) -> Vec<Message> {
+        let first_event = game.events.len();
+        let now = self.runtime.system_time();
+        let seat = game.active_seat as usize;
+        let bot = game.player(seat).owner;
+
+        let plan = match plan {
+            Some(plan) => {
+                validate_plan(game, seat, &plan, now, cards).expect("Illegal AI plan");
+                plan
+            }
+            None => {
+                let difficulty = game.bot_difficulty.unwrap_or_default();
+                let mut rng = game.rng(self.runtime.chain_id(), self.runtime.block_height());
+                plan_turn(game, seat, difficulty, &mut rng, now, cards)
+            }
+        };
+
+        let mut messages = vec![];
+        for action in plan {
+            game.apply_action(bot, &action, now, cards)
+                .expect("Bot planned an illegal action");
+            match action {
+                GameAction::PlayCard { card_id } => {
+                    if let Some(card) = find_card(cards, card_id) {
+                        messages.push(Message::CardPlayed { player: bot, card });
+                    }
+                }
+                GameAction::EndTurn => {
+                    let next_player = game.player(game.active_seat as usize).owner;
+                    messages.push(Message::TurnEnded { next_player });
+                }
+                _ => {}
+            }
+        }
+        messages.extend(zone_messages(game, first_event));
+        messages
+    }
+
+    /// The signer, who must be an admin.
+    fn admin_signer(&mut self) -> AccountOwner {
+        let owner = self
+            .runtime
+            .authenticated_signer()
+            .expect("Missing signer");
+        assert!(self.state.is_admin(&owner), "Only admins can do this");
+        owner
+    }
+
+    fn audit(&mut self, admin: AccountOwner, action: AdminAction, detail: String) {
+        let now = self.runtime.system_time();
+        self.state.record_admin_action(admin, now, action, detail);
+    }
+
+    fn has_ai_oracle(&mut self) -> bool {
+        self.runtime.application_parameters().ai_oracle_url.is_some()
+    }
+
+    /// The deck `owner` brings to a match of the given format.
+    async fn deck_for(&self, owner: &AccountOwner, format: DeckFormat) -> Vec<u32> {
+        match format {
+            DeckFormat::Constructed => (1..=10).collect(),
+            DeckFormat::Draft => self
+                .state
+                .get_draft_run(owner)
+                .await
+                .map(|run| run.deck)
+                .unwrap_or_default(),
+            DeckFormat::Sealed => self
+                .state
+                .get_sealed_pool(owner)
+                .await
+                .map(|pool| pool.deck)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Deals a deterministic draft offer for `owner`'s next pick.
+    async fn deal_draft_offer(&mut self, owner: &AccountOwner, pick: usize) -> Vec<u32> {
+        let mut rng = MatchRng::from_parts(&[
+            owner.to_string().as_bytes(),
+            &(pick as u64).to_le_bytes(),
+            self.runtime.chain_id().to_string().as_bytes(),
+            &self.runtime.block_height().0.to_le_bytes(),
+        ]);
+        draft_offer(&self.state.get_available_cards().await, &mut rng)
+    }
+
+    /// Records a draft match result, granting the run's rewards if it just
+    /// completed.
+    async fn record_draft_result(&mut self, owner: &AccountOwner, won: bool) {
+        let Some(mut run) = self.state.get_draft_run(owner).await else {
+            return;
+        };
+
+        if run.record_result(won) {
+            // Only list the rewards that made it into the collection
+            let mut granted = vec![];
+            for card_id in run.rewards {
+                if self.state.add_card_to_player(owner, card_id).await.is_ok() {
+                    granted.push(card_id);
+                }
+            }
+            run.rewards = granted;
+        }
+        self.state.update_draft_run(run).await.ok();
+    }
+}
+
+/// Zone-change messages for the events logged since `first_event`.
+fn zone_messages(game: &GameState, first_event: usize) -> Vec<Message> {
+    game.events[first_event..]
+        .iter()
+        .filter_map(|event| event.zone_change())
+        .map(Message::from)
+        .collect()
+}
@@ -0,0 +1,141 @@
+use crate::{rng::MatchRng, Card, DraftRun, DraftStatus};
+use linera_sdk::base::AccountOwner;
+
+/// Number of picks, and so cards, in a draft deck.
+pub const DRAFT_DECK_SIZE: usize = 10;
+/// Cards offered for each pick.
+pub const CARDS_PER_PICK: usize = 3;
+/// A run ends at this many wins...
+pub const DRAFT_MAX_WINS: u8 = 5;
+/// ...or this many losses.
+pub const DRAFT_MAX_LOSSES: u8 = 3;
+
+/// Offers `CARDS_PER_PICK` distinct cards from the card database.
+pub fn draft_offer(cards: &[Card], rng: &mut MatchRng) -> Vec<u32> {
+    let mut pool: Vec<u32> = cards.iter().map(|c| c.id).collect();
+    let mut offer = Vec::with_capacity(CARDS_PER_PICK);
+    while offer.len() < CARDS_PER_PICK && !pool.is_empty() {
+        let idx = rng.below(pool.len() as u32) as usize;
+        offer.push(pool.swap_remove(idx));
+    }
+    offer
+}
+
+impl DraftRun {
+    pub fn new(owner: AccountOwner, offer: Vec<u32>) -> Self {
+        DraftRun {
+            owner,
+            status: DraftStatus::Drafting,
+            offer,
+            deck: vec![],
+            wins: 0,
+            losses: 0,
+            rewards: vec![],
+        }
+    }
+
+    /// Takes one card from the current offer. Once the deck is full the run
+    /// moves on to playing; otherwise the caller deals the next offer.
+    pub fn pick(&mut self, choice: u8) -> Result<u32, String> {
+        if self.status != DraftStatus::Drafting {
+            return Err("Draft picks are already complete".to_string());
+        }
+        let card_id = *self
+            .offer
+            .get(choice as usize)
+            .ok_or("Choice is not in the current offer")?;
+
+        self.deck.push(card_id);
+        self.offer.clear();
+        if self.deck.len() >= DRAFT_DECK_SIZE {
+            self.status = DraftStatus::Playing;
+        }
+        Ok(card_id)
+    }
+
+    /// Records a finished draft match. Returns true if this completed the run.
+    pub fn record_result(&mut self, won: bool) -> bool {
+        if self.status != DraftStatus::Playing {
+            return false;
+        }
+        if won {
+            self.wins += 1;
+        } else {
+            self.losses += 1;
+        }
+
+        if self.wins >= DRAFT_MAX_WINS || self.losses >= DRAFT_MAX_LOSSES {
+            self.status = DraftStatus::Complete;
+            self.rewards = self.deck.iter().copied().take(self.reward_count()).collect();
+            return true;
+        }
+        false
+    }
+
+    /// The run keeps one drafted card, plus one more per win.
+    pub fn reward_count(&self) -> usize {
+        (self.wins as usize + 1).min(self.deck.len())
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.status != DraftStatus::Complete
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A run with a full deck of cards 1 to `DRAFT_DECK_SIZE`, ready to play.
+    fn drafted_run() -> DraftRun {
+        let mut run = DraftRun::new(AccountOwner::Address20([1; 20]), vec![]);
+        for card_id in 1..=DRAFT_DECK_SIZE as u32 {
+            run.offer = vec![card_id, 99];
+            run.pick(0).unwrap();
+        }
+        run
+    }
+
+    #[test]
+    fn the_deck_fills_one_pick_at_a_time() {
+        let mut run = DraftRun::new(AccountOwner::Address20([1; 20]), vec![7, 8, 9]);
+        assert!(run.pick(3).is_err());
+        assert_eq!(run.pick(1), Ok(8));
+        assert!(run.offer.is_empty());
+        assert_eq!(run.status, DraftStatus::Drafting);
+
+        let run = drafted_run();
+        assert_eq!(run.deck.len(), DRAFT_DECK_SIZE);
+        assert_eq!(run.status, DraftStatus::Playing);
+    }
+
+    #[test]
+    fn a_run_without_wins_keeps_one_card() {
+        let mut run = drafted_run();
+        for _ in 1..DRAFT_MAX_LOSSES {
+            assert!(!run.record_result(false));
+        }
+        assert!(run.record_result(false));
+        assert_eq!(run.status, DraftStatus::Complete);
+        assert_eq!(run.rewards, vec![1]);
+    }
+
+    #[test]
+    fn each_win_adds_a_reward() {
+        let mut run = drafted_run();
+        run.record_result(true);
+        run.record_result(true);
+        for _ in 0..DRAFT_MAX_LOSSES {
+            run.record_result(false);
+        }
+        assert_eq!(run.rewards, vec![1, 2, 3]);
+
+        let mut run = drafted_run();
+        for _ in 0..DRAFT_MAX_WINS {
+            run.record_result(true);
+        }
+        assert_eq!(run.rewards.len(), DRAFT_MAX_WINS as usize + 1);
+        assert!(!run.record_result(true));
+        assert_eq!(run.wins, DRAFT_MAX_WINS);
+    }
+}
@@ -1,7 +1,18 @@
-use async_graphql::{Request, Response, SimpleObject};
+use async_graphql::{Enum, Request, Response, SimpleObject};
 use linera_sdk::base::{AccountOwner, Amount, ChainId, Timestamp};
 use serde::{Deserialize, Serialize};
 
+pub mod ai;
+pub mod draft;
+pub mod puzzle;
+pub mod rng;
+pub mod rules;
+pub mod schema;
+pub mod sealed;
+pub mod series;
+pub mod simulation;
+pub mod state;
+
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
 pub struct Card {
     pub id: u32,
@@ -11,10 +22,12 @@ pub struct Card {
     pub defense: u32,
     pub cost: u32,
     pub card_type: CardType,
+    pub rarity: Rarity,
     pub ability: Option<String>,
+    pub win_condition: Option<WinCondition>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
 pub enum CardType {
     Creature,
     Spell,
@@ -22,6 +35,30 @@ pub enum CardType {
     Buff,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum Rarity {
+    Common,
+    Uncommon,
+    Rare,
+    Legendary,
+}
+
+/// A card-defined alternate way to win, checked at the start of its
+/// controller's turn while the card is on the field.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct WinCondition {
+    pub kind: WinConditionKind,
+    pub threshold: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum WinConditionKind {
+    /// Control at least `threshold` creatures.
+    ControlCreatures,
+    /// Have at least `threshold` cards in your graveyard.
+    FilledGraveyard,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
 pub struct PlayerStats {
     pub owner: AccountOwner,
@@ -30,58 +67,509 @@ pub struct PlayerStats {
     pub draws: u32,
     pub ranking: u32,
     pub total_matches: u32,
+    /// One entry per copy owned, so rewards of a card already owned still
+    /// count.
     pub cards_owned: Vec<u32>,
+    pub puzzles_solved: Vec<u64>,
+    /// Results against the built-in AI, kept out of the PvP record and ranking.
+    pub pve: PveStats,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+pub struct PveStats {
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct LeaderboardEntry {
+    /// Position on the leaderboard, 1 being the top. Equal ratings are
+    /// ordered by owner.
+    pub rank: u32,
+    pub owner: AccountOwner,
+    pub ranking: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+}
+
+/// A match between two or more players.
+///
+/// `players` is in seat order and turns pass clockwise between the seats
+/// still in the game. `winner` holds the winning team id.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
 pub struct GameState {
     pub match_id: u64,
-    pub player1: PlayerInGame,
-    pub player2: PlayerInGame,
+    pub series_id: Option<u64>,
+    pub mode: MatchMode,
+    pub format: DeckFormat,
+    pub players: Vec<PlayerInGame>,
+    pub active_seat: u8,
     pub current_turn: u8,
+    pub started_at: Timestamp,
     pub turn_timer: Timestamp,
     pub game_phase: GamePhase,
     pub winner: Option<u8>,
+    pub elimination_order: Vec<AccountOwner>,
+    pub end_reason: Option<EndReason>,
+    pub events: Vec<GameEvent>,
+    /// How the bot seat plays, for matches against the built-in AI.
+    pub bot_difficulty: Option<AiDifficulty>,
+    /// Bot turns wait for `RequestAIMove` so the AI oracle can plan them,
+    /// instead of running straight after the human's turn.
+    pub bot_uses_oracle: bool,
+}
+
+/// `PlayerStats` as stored, tagged with its layout. When the layout changes,
+/// keep the old struct for its variant and upgrade it in `schema.rs`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum VersionedPlayerStats {
+    V1(PlayerStats),
+}
+
+/// `GameState` as stored, tagged with its layout.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum VersionedGameState {
+    V1(GameState),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum AiDifficulty {
+    /// Random legal moves.
+    Easy,
+    /// Greedy board evaluation.
+    #[default]
+    Medium,
+    /// Bounded one-round lookahead over the rules engine.
+    Hard,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum MatchMode {
+    /// One against one; the only mode that can be played as a series.
+    Duel,
+    /// Three or four players, each on their own team.
+    FreeForAll,
+    /// Two teams of two sharing victory.
+    Teams,
+}
+
+/// Where the players' decks come from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum DeckFormat {
+    /// The starter deck.
+    Constructed,
+    /// The deck built during the player's current draft run.
+    Draft,
+    /// The deck built from the player's sealed pool.
+    Sealed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum DraftStatus {
+    /// Still picking cards.
+    Drafting,
+    /// Deck complete; playing matches with it.
+    Playing,
+    /// Reached the win or loss limit; rewards have been granted.
+    Complete,
+}
+
+/// A player's draft (arena) run. Picks build a temporary deck that is
+/// played until the run reaches its win or loss limit.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct DraftRun {
+    pub owner: AccountOwner,
+    pub status: DraftStatus,
+    /// The cards on offer for the next pick.
+    pub offer: Vec<u32>,
+    pub deck: Vec<u32>,
+    pub wins: u8,
+    pub losses: u8,
+    /// Cards added to the player's collection when the run completed.
+    pub rewards: Vec<u32>,
+}
+
+/// A player's sealed pool and the deck they built from it.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct SealedPool {
+    pub owner: AccountOwner,
+    pub pool: Vec<u32>,
+    /// Empty until a deck has been built with `BuildSealedDeck`.
+    pub deck: Vec<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
 pub struct PlayerInGame {
     pub owner: AccountOwner,
+    pub seat: u8,
+    pub team: u8,
     pub health: i32,
     pub mana: u32,
     pub hand: Vec<Card>,
     pub deck: Vec<u32>,
     pub field: Vec<Card>,
     pub graveyard: Vec<u32>,
+    pub exile: Vec<u32>,
+    pub decked_out: bool,
+    pub eliminated: bool,
+    /// Whether the creature in each `field` slot has attacked this turn.
+    /// Slots past the end haven't.
+    pub attacked: Vec<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum SeriesLength {
+    Bo1,
+    Bo3,
+    Bo5,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum MatchResult {
+    Win,
+    Loss,
+    Draw,
+}
+
+/// A finished match, archived when it leaves `active_matches`.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct MatchRecord {
+    pub match_id: u64,
+    pub series_id: Option<u64>,
+    pub mode: MatchMode,
+    pub format: DeckFormat,
+    pub players: Vec<AccountOwner>,
+    /// Each player's decklist in seat order, sorted by card id.
+    pub decks: Vec<Vec<u32>>,
+    /// The winning team, as in `GameState::winner`.
+    pub winner: Option<u8>,
+    pub winners: Vec<AccountOwner>,
+    pub turns: u8,
+    pub started_at: Timestamp,
+    pub ended_at: Timestamp,
+    pub end_reason: Option<EndReason>,
+    pub final_state: GameState,
 }
 
+/// A best-of-N series between two players. Each game is a fresh `GameState`;
+/// only the series result counts towards player stats.
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct Series {
+    pub series_id: u64,
+    pub player1: AccountOwner,
+    pub player2: AccountOwner,
+    pub length: SeriesLength,
+    pub player1_wins: u8,
+    pub player2_wins: u8,
+    pub games_played: u8,
+    pub match_ids: Vec<u64>,
+    /// Closed early by a no-contest game; the series has no result.
+    pub no_contest: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum EndReason {
+    /// A player's health dropped to 0 or below.
+    Health,
+    /// A player had to draw from an empty deck.
+    DeckOut,
+    /// A card's alternate win condition was met.
+    AlternateWin,
+    /// An admin ended the match with `ForceEndMatch`.
+    AdminEnded,
+    /// A player sat idle past the abandon timeout and forfeited.
+    Forfeit,
+    /// Abandoned with nobody to blame; no result is recorded.
+    NoContest,
+}
+
+/// The zones a card can occupy during a match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum Zone {
+    Deck,
+    Hand,
+    Field,
+    Graveyard,
+    Exile,
+}
+
+/// A single card moving between two of a player's zones.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct ZoneChange {
+    pub player: AccountOwner,
+    pub card_id: u32,
+    pub from: Zone,
+    pub to: Zone,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
 pub enum GamePhase {
-    WaitingForPlayers,
-    Player1Turn,
-    Player2Turn,
+    /// Being played; whose turn it is is `GameState::active_seat`.
+    InProgress,
     Finished,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum Operation {
-    CreatePlayerProfile,
-    CreateMatch { opponent: AccountOwner },
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum GameEventKind {
+    CardDrawn,
+    CardPlayed,
+    Attack,
+    Damage,
+    CreatureDied,
+    CounterActivated,
+    ZoneChanged,
+    TurnChanged,
+    RandomDraw,
+    PlayerEliminated,
+}
+
+/// One entry in a match's ordered event log.
+///
+/// `player` is the player the event happened to or was caused by; the
+/// optional fields are filled in depending on `kind`.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct GameEvent {
+    pub index: u32,
+    pub turn: u8,
+    pub kind: GameEventKind,
+    pub player: AccountOwner,
+    pub card_id: Option<u32>,
+    pub target_player: Option<AccountOwner>,
+    pub target_card: Option<u32>,
+    pub amount: Option<i32>,
+    pub from: Option<Zone>,
+    pub to: Option<Zone>,
+}
+
+/// A single move within a match, as submitted in a puzzle solution.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameAction {
     PlayCard { card_id: u32 },
     InstantCounter { card_id: u32, target_card: u32 },
+    AttackPlayer { attacker_id: u32, target: Option<AccountOwner> },
+    AttackCreature { attacker_id: u32, defender_id: u32, target: Option<AccountOwner> },
     EndTurn,
-    AttackPlayer { attacker_id: u32 },
-    AttackCreature { attacker_id: u32, defender_id: u32 },
-    RequestAIMove,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum ActionKind {
+    PlayCard,
+    InstantCounter,
+    AttackPlayer,
+    AttackCreature,
+    EndTurn,
+}
+
+/// A `GameAction` flattened for GraphQL. `card_id` is the card played or
+/// the attacker; `target_card` is the countered card or the defender.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct LegalAction {
+    pub kind: ActionKind,
+    pub card_id: Option<u32>,
+    pub target_player: Option<AccountOwner>,
+    pub target_card: Option<u32>,
+}
+
+/// The AI evaluator's view of a position, from one player's side.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct ScoreBreakdown {
+    pub health: i64,
+    pub board: i64,
+    pub hand: i64,
+    pub total: i64,
+}
+
+/// A hint: the AI's recommended next action and why.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct MoveSuggestion {
+    pub action: LegalAction,
+    pub explanation: String,
+    /// How much the action improves the evaluator's score.
+    pub score_change: i64,
+    /// Score of the position after the action.
+    pub breakdown: ScoreBreakdown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum PuzzleObjective {
+    /// Win the match before the turn ends.
+    WinThisTurn,
+    /// Leave every opponent without a creature on the field.
+    ClearEnemyField,
+}
+
+/// A curated board position to solve. The solver takes seat 0, which
+/// must be the active seat in `start`.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct Puzzle {
+    pub puzzle_id: u64,
+    pub name: String,
+    pub description: String,
+    pub start: GameState,
+    pub objective: PuzzleObjective,
+}
+
+/// Application parameters, fixed when the application is created.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Parameters {
+    /// Endpoint the service asks for the bot's moves through the HTTP oracle.
+    /// Its host must be on the committee's HTTP allow list.
+    pub ai_oracle_url: Option<String>,
+    /// Admins for a deployment upgraded from before admin accounts existed,
+    /// installed by the migration if none are set. New deployments name
+    /// theirs in `InstantiationArgument`.
+    pub admins: Vec<AccountOwner>,
+}
+
+/// Body POSTed to the AI oracle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AiMoveRequest {
+    pub seat: u8,
+    pub difficulty: AiDifficulty,
+    pub game: GameState,
+    pub legal_actions: Vec<GameAction>,
+}
+
+/// The oracle's reply: the bot's whole turn, in order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AiMoveResponse {
+    pub actions: Vec<GameAction>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InstantiationArgument {
+    /// Accounts allowed to run admin operations.
+    pub admins: Vec<AccountOwner>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum AdminAction {
+    AddCard,
+    UpdateCard,
+    RetireCard,
+    BanPlayer,
+    UnbanPlayer,
+    ForceEndMatch,
+    RotateAdmins,
+    AddPuzzle,
+    MigrateState,
+}
+
+/// One admin operation, as kept in the audit log.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct AuditEntry {
+    /// Position in the audit log.
+    pub index: u32,
+    pub admin: AccountOwner,
+    pub timestamp: Timestamp,
+    pub action: AdminAction,
+    /// What was changed, e.g. the card id or banned player.
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Operation {
+    CreatePlayerProfile,
+    /// Pass `ai::BOT_OWNER` as `opponent` to play the built-in AI.
+    CreateMatch { opponent: AccountOwner, series: SeriesLength },
+    /// Free-for-all with 2–3 opponents, or 2v2 where `opponents` is
+    /// `[teammate, enemy, enemy]`.
+    CreateMultiplayerMatch { opponents: Vec<AccountOwner>, mode: MatchMode },
+    StartDraft,
+    /// Takes the card at `choice` in the current draft offer.
+    DraftPick { choice: u8 },
+    /// Plays a draft match; both players need a run with a completed deck.
+    CreateDraftMatch { opponent: AccountOwner },
+    OpenSealedPool,
+    /// Builds a deck using only cards from the player's sealed pool.
+    BuildSealedDeck { cards: Vec<u32> },
+    /// Waits for, or is paired with, another player with a sealed deck.
+    JoinSealedQueue,
+    PlayCard { match_id: u64, card_id: u32 },
+    InstantCounter { match_id: u64, card_id: u32, target_card: u32 },
+    EndTurn { match_id: u64 },
+    /// `target` may be omitted when only one opponent is left.
+    AttackPlayer {
+        match_id: u64,
+        attacker_id: u32,
+        target: Option<AccountOwner>,
+    },
+    AttackCreature {
+        match_id: u64,
+        attacker_id: u32,
+        defender_id: u32,
+        target: Option<AccountOwner>,
+    },
+    /// Starts a duel against the built-in AI. With `use_oracle` the bot's
+    /// turns wait for `RequestAIMove` instead of running straight after yours.
+    CreateBotMatch {
+        difficulty: AiDifficulty,
+        use_oracle: bool,
+    },
+    /// Has the AI play its turn in one of the caller's matches. `plan` is the
+    /// AI oracle's turn, scheduled by the service's `requestAiMove` mutation in
+    /// matches that use the oracle; it must pass `validate_plan`. Without it
+    /// the built-in AI plays.
+    RequestAIMove {
+        match_id: u64,
+        plan: Option<Vec<GameAction>>,
+    },
+    /// Admin only: publishes a new puzzle.
+    AddPuzzle {
+        name: String,
+        description: String,
+        start: GameState,
+        objective: PuzzleObjective,
+    },
+    /// Submits a solution, checked by replaying it through the rules engine.
+    SolvePuzzle { puzzle_id: u64, actions: Vec<GameAction> },
+    /// Admin only: upgrades up to `batch_size` stored records to the current
+    /// schema. Every operation already migrates a small batch while one is
+    /// pending; this just speeds it up.
+    MigrateState { batch_size: u32 },
+    /// Admin only: adds a card under a new id.
+    AddCard { card: Card },
+    /// Admin only: replaces the card with the same id.
+    UpdateCard { card: Card },
+    /// Admin only: stops a card from being dealt into new drafts and sealed
+    /// pools. Decks and matches that have it keep it.
+    RetireCard { card_id: u32 },
+    /// Admin only: blocks every operation from `player`. Series they are in
+    /// still deal their remaining games, which the abandon sweep can settle.
+    BanPlayer { player: AccountOwner, reason: String },
+    /// Admin only.
+    UnbanPlayer { player: AccountOwner },
+    /// Admin only: ends a match with `winner` as the winning team, or as a
+    /// draw. Stats and series are updated as for any finished match.
+    ForceEndMatch { match_id: u64, winner: Option<u8> },
+    /// Admin only: replaces the admin list, which can't be left empty.
+    RotateAdmins { admins: Vec<AccountOwner> },
+    /// Ends matches whose active player has been idle too long. Anyone may
+    /// call it, once per `SWEEP_INTERVAL`.
+    SweepAbandonedMatches,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Message {
-    MatchCreated { match_chain: ChainId, player1: AccountOwner, player2: AccountOwner },
+    MatchCreated { match_chain: ChainId, players: Vec<AccountOwner> },
     CardPlayed { player: AccountOwner, card: Card },
     TurnEnded { next_player: AccountOwner },
-    GameFinished { winner: Option<AccountOwner>, rewards: Amount },
+    GameFinished { winners: Vec<AccountOwner>, rewards: Amount },
     CounterActivated { player: AccountOwner, counter_card: Card, target_card: u32 },
+    ZoneChanged { player: AccountOwner, card_id: u32, from: Zone, to: Zone },
+}
+
+impl From<ZoneChange> for Message {
+    fn from(change: ZoneChange) -> Self {
+        Message::ZoneChanged {
+            player: change.player,
+            card_id: change.card_id,
+            from: change.from,
+            to: change.to,
+        }
+    }
 }
 
 pub fn create_starter_deck() -> Vec<Card> {
@@ -94,7 +582,9 @@ pub fn create_starter_deck() -> Vec<Card> {
             defense: 0,
             cost: 1,
             card_type: CardType::Spell,
+            rarity: Rarity::Common,
             ability: None,
+            win_condition: None,
         },
         Card {
             id: 2,
@@ -104,7 +594,9 @@ pub fn create_starter_deck() -> Vec<Card> {
             defense: 5,
             cost: 3,
             card_type: CardType::Creature,
+            rarity: Rarity::Common,
             ability: None,
+            win_condition: None,
         },
         Card {
             id: 3,
@@ -114,7 +606,9 @@ pub fn create_starter_deck() -> Vec<Card> {
             defense: 2,
             cost: 3,
             card_type: CardType::Creature,
+            rarity: Rarity::Uncommon,
             ability: Some("First Strike".to_string()),
+            win_condition: None,
         },
         Card {
             id: 4,
@@ -124,7 +618,9 @@ pub fn create_starter_deck() -> Vec<Card> {
             defense: 0,
             cost: 2,
             card_type: CardType::Counter,
+            rarity: Rarity::Rare,
             ability: Some("Instant".to_string()),
+            win_condition: None,
         },
         Card {
             id: 5,
@@ -134,7 +630,9 @@ pub fn create_starter_deck() -> Vec<Card> {
             defense: 0,
             cost: 2,
             card_type: CardType::Buff,
+            rarity: Rarity::Uncommon,
             ability: None,
+            win_condition: None,
         },
         Card {
             id: 6,
@@ -144,7 +642,9 @@ pub fn create_starter_deck() -> Vec<Card> {
             defense: 5,
             cost: 5,
             card_type: CardType::Creature,
+            rarity: Rarity::Rare,
             ability: None,
+            win_condition: None,
         },
         Card {
             id: 7,
@@ -154,7 +654,9 @@ pub fn create_starter_deck() -> Vec<Card> {
             defense: 0,
             cost: 0,
             card_type: CardType::Spell,
+            rarity: Rarity::Uncommon,
             ability: None,
+            win_condition: None,
         },
         Card {
             id: 8,
@@ -164,7 +666,9 @@ pub fn create_starter_deck() -> Vec<Card> {
             defense: 3,
             cost: 4,
             card_type: CardType::Creature,
+            rarity: Rarity::Uncommon,
             ability: Some("Flying".to_string()),
+            win_condition: None,
         },
         Card {
             id: 9,
@@ -174,7 +678,9 @@ pub fn create_starter_deck() -> Vec<Card> {
             defense: 0,
             cost: 2,
             card_type: CardType::Spell,
+            rarity: Rarity::Common,
             ability: None,
+            win_condition: None,
         },
         Card {
             id: 10,
@@ -184,7 +690,9 @@ pub fn create_starter_deck() -> Vec<Card> {
             defense: 3,
             cost: 4,
             card_type: CardType::Creature,
+            rarity: Rarity::Rare,
             ability: None,
+            win_condition: None,
         },
     ]
 }
@@ -0,0 +1,86 @@
+use crate::{Card, GameAction, GamePhase, GameState, Puzzle, PuzzleObjective};
+use linera_sdk::base::{AccountOwner, Timestamp};
+
+/// Seat the solver plays from.
+pub const SOLVER_SEAT: usize = 0;
+
+impl Puzzle {
+    /// Checks a starting position can be published as a puzzle.
+    pub fn new(
+        puzzle_id: u64,
+        name: String,
+        description: String,
+        start: GameState,
+        objective: PuzzleObjective,
+    ) -> Result<Self, String> {
+        if start.players.len() < 2 {
+            return Err("A puzzle needs at least two players".to_string());
+        }
+        if start.game_phase == GamePhase::Finished {
+            return Err("A puzzle can't start from a finished match".to_string());
+        }
+        if start.active_seat as usize != SOLVER_SEAT {
+            return Err("The solver's seat must be the active seat".to_string());
+        }
+        if start.opponent_seats(SOLVER_SEAT).is_empty() {
+            return Err("A puzzle needs an opponent".to_string());
+        }
+        if objective.is_met(&start, SOLVER_SEAT) {
+            return Err("Objective is already met in the starting position".to_string());
+        }
+
+        Ok(Puzzle {
+            puzzle_id,
+            name,
+            description,
+            start,
+            objective,
+        })
+    }
+
+    /// Replays `actions` for `solver` from the starting position and checks
+    /// the objective is met. Solutions must finish within the solver's turn.
+    pub fn verify(
+        &self,
+        solver: AccountOwner,
+        actions: &[GameAction],
+        now: Timestamp,
+        cards: &[Card],
+    ) -> Result<GameState, String> {
+        let mut game = self.start.clone();
+        if game
+            .seat_of(&solver)
+            .is_some_and(|seat| seat != SOLVER_SEAT)
+        {
+            return Err("Solver already plays another seat in this puzzle".to_string());
+        }
+        game.players[SOLVER_SEAT].owner = solver;
+
+        for action in actions {
+            if *action == GameAction::EndTurn {
+                return Err("Puzzles must be solved within the turn".to_string());
+            }
+            game.apply_action(solver, action, now, cards)?;
+        }
+
+        if !self.objective.is_met(&game, SOLVER_SEAT) {
+            return Err("Objective not met".to_string());
+        }
+        Ok(game)
+    }
+}
+
+impl PuzzleObjective {
+    pub fn is_met(&self, game: &GameState, seat: usize) -> bool {
+        match self {
+            PuzzleObjective::WinThisTurn => {
+                game.game_phase == GamePhase::Finished
+                    && game.winner == Some(game.players[seat].team)
+            }
+            PuzzleObjective::ClearEnemyField => game
+                .opponent_seats(seat)
+                .iter()
+                .all(|opponent| game.players[*opponent].field.is_empty()),
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use crate::{GameEvent, GameEventKind, GameState};
+use linera_sdk::base::{AccountOwner, BlockHeight, ChainId};
+
+/// Deterministic random number generator for in-contract randomness.
+///
+/// Every validator executing the same block derives the same seed, so random
+/// card effects, shuffles and pack openings resolve identically everywhere.
+/// This is a SplitMix64 stream: fast, tiny and good enough for game effects,
+/// but not suitable for anything security sensitive.
+#[derive(Debug, Clone)]
+pub struct MatchRng {
+    state: u64,
+}
+
+impl MatchRng {
+    /// Seeds a generator for one operation in a match.
+    ///
+    /// `nonce` separates operations that land in the same block and turn;
+    /// callers pass the match's event count so every operation gets a fresh stream.
+    pub fn new(
+        match_id: u64,
+        turn: u8,
+        chain_id: ChainId,
+        block_height: BlockHeight,
+        nonce: u64,
+    ) -> Self {
+        Self::from_parts(&[
+            &match_id.to_le_bytes(),
+            &[turn],
+            chain_id.to_string().as_bytes(),
+            &block_height.0.to_le_bytes(),
+            &nonce.to_le_bytes(),
+        ])
+    }
+
+    /// Seeds a generator by hashing arbitrary deterministic inputs, for
+    /// randomness outside a match such as draft offers.
+    pub fn from_parts(parts: &[&[u8]]) -> Self {
+        let hash = parts.iter().fold(FNV_OFFSET, |hash, part| fnv1a(hash, part));
+        Self::from_seed(hash)
+    }
+
+    pub fn from_seed(seed: u64) -> Self {
+        MatchRng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `0..bound`. `bound` must be non-zero.
+    pub fn below(&mut self, bound: u32) -> u32 {
+        assert!(bound > 0, "bound must be non-zero");
+        // Rejection sampling keeps the result unbiased.
+        let bound = bound as u64;
+        let zone = u64::MAX - (u64::MAX % bound);
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return (value % bound) as u32;
+            }
+        }
+    }
+}
+
+const FNV_OFFSET: u64 = 0xCBF2_9CE4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01B3;
+
+fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+impl GameState {
+    /// Seeds the RNG for the operation currently being executed on this match.
+    pub fn rng(&self, chain_id: ChainId, block_height: BlockHeight) -> MatchRng {
+        MatchRng::new(
+            self.match_id,
+            self.current_turn,
+            chain_id,
+            block_height,
+            self.events.len() as u64,
+        )
+    }
+
+    /// Draws a value in `0..bound` on behalf of `player` and logs it, so a
+    /// replay of the event log reproduces every random outcome.
+    pub fn roll(&mut self, rng: &mut MatchRng, player: AccountOwner, bound: u32) -> u32 {
+        let value = rng.below(bound);
+        self.log(GameEvent::new(GameEventKind::RandomDraw, player).with_amount(value as i32));
+        value
+    }
+
+    /// Shuffles a player's deck with logged rolls.
+    pub fn shuffle_deck(&mut self, seat: usize, rng: &mut MatchRng) {
+        let owner = self.players[seat].owner;
+        let len = self.players[seat].deck.len();
+        for i in (1..len).rev() {
+            let j = self.roll(rng, owner, i as u32 + 1) as usize;
+            self.players[seat].deck.swap(i, j);
+        }
+    }
+}
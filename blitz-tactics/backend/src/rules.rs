@@ -0,0 +1,1160 @@
+use crate::{
+    ai::BOT_OWNER,
+    ActionKind, Card, CardType, DeckFormat, EndReason, GameAction, GameEvent, GameEventKind,
+    GamePhase, GameState, LegalAction, MatchMode, MatchRecord, MatchResult, PlayerInGame,
+    WinCondition, WinConditionKind, Zone, ZoneChange,
+};
+use linera_sdk::base::{AccountOwner, TimeDelta, Timestamp};
+
+/// Number of cards each player draws before the first turn.
+pub const OPENING_HAND_SIZE: usize = 5;
+pub const STARTING_HEALTH: i32 = 20;
+pub const STARTING_MANA: u32 = 3;
+
+pub fn find_card(cards: &[Card], card_id: u32) -> Option<Card> {
+    cards.iter().find(|c| c.id == card_id).cloned()
+}
+
+impl PlayerInGame {
+    pub fn new(owner: AccountOwner, seat: u8, team: u8, deck: Vec<u32>) -> Self {
+        PlayerInGame {
+            owner,
+            seat,
+            team,
+            health: STARTING_HEALTH,
+            mana: STARTING_MANA,
+            hand: vec![],
+            deck,
+            field: vec![],
+            graveyard: vec![],
+            exile: vec![],
+            decked_out: false,
+            eliminated: false,
+            attacked: vec![],
+        }
+    }
+
+    pub fn is_bot(&self) -> bool {
+        self.owner == BOT_OWNER
+    }
+
+    /// Moves a card between two of this player's zones.
+    ///
+    /// This is the only way cards change zones, so every move produces a
+    /// `ZoneChange` the caller can broadcast. Returns `None` if the card is
+    /// not in `from`.
+    pub fn move_card(
+        &mut self,
+        card_id: u32,
+        from: Zone,
+        to: Zone,
+        cards: &[Card],
+    ) -> Option<ZoneChange> {
+        if from == to {
+            return None;
+        }
+
+        let card = self.take_card(card_id, from, cards)?;
+        Some(self.place_card(card, from, to))
+    }
+
+    /// Moves the creature in field slot `slot` to another zone, for when it
+    /// matters which copy of a card leaves.
+    pub fn move_field_card(&mut self, slot: usize, to: Zone) -> Option<ZoneChange> {
+        if to == Zone::Field || slot >= self.field.len() {
+            return None;
+        }
+        let card = self.take_field_slot(slot);
+        Some(self.place_card(card, Zone::Field, to))
+    }
+
+    fn place_card(&mut self, card: Card, from: Zone, to: Zone) -> ZoneChange {
+        let card_id = card.id;
+        match to {
+            Zone::Deck => self.deck.push(card.id),
+            Zone::Hand => self.hand.push(card),
+            Zone::Field => self.field.push(card),
+            Zone::Graveyard => self.graveyard.push(card.id),
+            Zone::Exile => self.exile.push(card.id),
+        }
+
+        ZoneChange {
+            player: self.owner,
+            card_id,
+            from,
+            to,
+        }
+    }
+
+    /// Card ids currently in the given zone.
+    pub fn zone_card_ids(&self, zone: Zone) -> Vec<u32> {
+        match zone {
+            Zone::Deck => self.deck.clone(),
+            Zone::Hand => self.hand.iter().map(|c| c.id).collect(),
+            Zone::Field => self.field.iter().map(|c| c.id).collect(),
+            Zone::Graveyard => self.graveyard.clone(),
+            Zone::Exile => self.exile.clone(),
+        }
+    }
+
+    /// Every card this player brought to the match, sorted by id. Cards
+    /// never leave their owner's zones, so this is the starting deck.
+    pub fn decklist(&self) -> Vec<u32> {
+        let zones = [Zone::Deck, Zone::Hand, Zone::Field, Zone::Graveyard, Zone::Exile];
+        let mut cards: Vec<u32> = zones
+            .into_iter()
+            .flat_map(|zone| self.zone_card_ids(zone))
+            .collect();
+        cards.sort_unstable();
+        cards
+    }
+
+    /// Whether this player has lost through health or decking out.
+    pub fn has_lost(&self) -> bool {
+        self.health <= 0 || self.decked_out
+    }
+
+    fn take_card(&mut self, card_id: u32, zone: Zone, cards: &[Card]) -> Option<Card> {
+        // Hand and field hold full cards (which may carry buffs); the other
+        // zones only hold ids and are resolved against the card database.
+        let ids = match zone {
+            Zone::Hand => return take_by_id(&mut self.hand, card_id),
+            Zone::Field => {
+                let slot = self.field.iter().position(|c| c.id == card_id)?;
+                return Some(self.take_field_slot(slot));
+            }
+            Zone::Deck => &mut self.deck,
+            Zone::Graveyard => &mut self.graveyard,
+            Zone::Exile => &mut self.exile,
+        };
+
+        let card = find_card(cards, card_id)?;
+        let idx = ids.iter().position(|id| *id == card_id)?;
+        ids.remove(idx);
+        Some(card)
+    }
+
+    /// Removes a field slot, along with its attack flag.
+    fn take_field_slot(&mut self, slot: usize) -> Card {
+        if slot < self.attacked.len() {
+            self.attacked.remove(slot);
+        }
+        self.field.remove(slot)
+    }
+
+    pub fn has_attacked(&self, slot: usize) -> bool {
+        self.attacked.get(slot).copied().unwrap_or(false)
+    }
+
+    /// The field slot of the first copy of `card_id` that hasn't attacked
+    /// this turn.
+    fn ready_attacker(&self, card_id: u32) -> Result<usize, String> {
+        if !self.field.iter().any(|c| c.id == card_id) {
+            return Err("Attacker is not on the field".to_string());
+        }
+        (0..self.field.len())
+            .find(|slot| self.field[*slot].id == card_id && !self.has_attacked(*slot))
+            .ok_or_else(|| "Creature has already attacked this turn".to_string())
+    }
+
+    fn mark_attacked(&mut self, slot: usize) {
+        if self.attacked.len() <= slot {
+            self.attacked.resize(slot + 1, false);
+        }
+        self.attacked[slot] = true;
+    }
+}
+
+impl WinCondition {
+    pub fn is_met(&self, player: &PlayerInGame) -> bool {
+        let count = match self.kind {
+            WinConditionKind::ControlCreatures => player
+                .field
+                .iter()
+                .filter(|c| matches!(c.card_type, CardType::Creature))
+                .count(),
+            WinConditionKind::FilledGraveyard => player.graveyard.len(),
+        };
+        count >= self.threshold as usize
+    }
+}
+
+impl GameEvent {
+    pub fn new(kind: GameEventKind, player: AccountOwner) -> Self {
+        GameEvent {
+            index: 0,
+            turn: 0,
+            kind,
+            player,
+            card_id: None,
+            target_player: None,
+            target_card: None,
+            amount: None,
+            from: None,
+            to: None,
+        }
+    }
+
+    pub fn with_card(mut self, card_id: u32) -> Self {
+        self.card_id = Some(card_id);
+        self
+    }
+
+    pub fn with_target_player(mut self, target: AccountOwner) -> Self {
+        self.target_player = Some(target);
+        self
+    }
+
+    pub fn with_target_card(mut self, target: u32) -> Self {
+        self.target_card = Some(target);
+        self
+    }
+
+    pub fn with_amount(mut self, amount: i32) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+
+    /// The zone change this event describes, if any.
+    pub fn zone_change(&self) -> Option<ZoneChange> {
+        Some(ZoneChange {
+            player: self.player,
+            card_id: self.card_id?,
+            from: self.from?,
+            to: self.to?,
+        })
+    }
+}
+
+impl From<GameAction> for LegalAction {
+    fn from(action: GameAction) -> Self {
+        let mut view = LegalAction {
+            kind: ActionKind::EndTurn,
+            card_id: None,
+            target_player: None,
+            target_card: None,
+        };
+        match action {
+            GameAction::PlayCard { card_id } => {
+                view.kind = ActionKind::PlayCard;
+                view.card_id = Some(card_id);
+            }
+            GameAction::InstantCounter {
+                card_id,
+                target_card,
+            } => {
+                view.kind = ActionKind::InstantCounter;
+                view.card_id = Some(card_id);
+                view.target_card = Some(target_card);
+            }
+            GameAction::AttackPlayer {
+                attacker_id,
+                target,
+            } => {
+                view.kind = ActionKind::AttackPlayer;
+                view.card_id = Some(attacker_id);
+                view.target_player = target;
+            }
+            GameAction::AttackCreature {
+                attacker_id,
+                defender_id,
+                target,
+            } => {
+                view.kind = ActionKind::AttackCreature;
+                view.card_id = Some(attacker_id);
+                view.target_player = target;
+                view.target_card = Some(defender_id);
+            }
+            GameAction::EndTurn => {}
+        }
+        view
+    }
+}
+
+impl From<ZoneChange> for GameEvent {
+    fn from(change: ZoneChange) -> Self {
+        let kind = match (change.from, change.to) {
+            (Zone::Deck, Zone::Hand) => GameEventKind::CardDrawn,
+            (Zone::Field, Zone::Graveyard) => GameEventKind::CreatureDied,
+            _ => GameEventKind::ZoneChanged,
+        };
+        let mut event = GameEvent::new(kind, change.player).with_card(change.card_id);
+        event.from = Some(change.from);
+        event.to = Some(change.to);
+        event
+    }
+}
+
+/// Seats and teams for a multiplayer match created by `creator`.
+///
+/// Free-for-all puts everyone on their own team. In 2v2, `opponents` is
+/// `[teammate, enemy, enemy]` and seats alternate between the teams.
+pub fn multiplayer_seats(
+    creator: AccountOwner,
+    opponents: &[AccountOwner],
+    mode: MatchMode,
+) -> Result<Vec<(AccountOwner, u8)>, String> {
+    let seats: Vec<(AccountOwner, u8)> = match (mode, opponents) {
+        (MatchMode::FreeForAll, [_, _] | [_, _, _]) => std::iter::once(creator)
+            .chain(opponents.iter().copied())
+            .enumerate()
+            .map(|(seat, owner)| (owner, seat as u8))
+            .collect(),
+        (MatchMode::FreeForAll, _) => {
+            return Err("Free-for-all needs 2 or 3 opponents".to_string());
+        }
+        (MatchMode::Teams, [teammate, enemy1, enemy2]) => {
+            vec![(creator, 0), (*enemy1, 1), (*teammate, 0), (*enemy2, 1)]
+        }
+        (MatchMode::Teams, _) => {
+            return Err("2v2 needs a teammate and two enemies".to_string());
+        }
+        (MatchMode::Duel, _) => return Err("Duels are created with CreateMatch".to_string()),
+    };
+
+    for (idx, (owner, _)) in seats.iter().enumerate() {
+        if seats[..idx].iter().any(|(other, _)| other == owner) {
+            return Err("A player can only take one seat".to_string());
+        }
+    }
+    Ok(seats)
+}
+
+impl GameState {
+    /// Creates a match with `players` already in seat order; seat 0 acts first.
+    pub fn new(
+        match_id: u64,
+        series_id: Option<u64>,
+        mode: MatchMode,
+        format: DeckFormat,
+        players: Vec<PlayerInGame>,
+        now: Timestamp,
+    ) -> Self {
+        GameState {
+            match_id,
+            series_id,
+            mode,
+            format,
+            players,
+            active_seat: 0,
+            current_turn: 1,
+            started_at: now,
+            turn_timer: now,
+            game_phase: GamePhase::InProgress,
+            winner: None,
+            elimination_order: vec![],
+            end_reason: None,
+            events: vec![],
+            bot_difficulty: None,
+            bot_uses_oracle: false,
+        }
+    }
+
+    pub fn player(&self, seat: usize) -> &PlayerInGame {
+        &self.players[seat]
+    }
+
+    pub fn player_mut(&mut self, seat: usize) -> &mut PlayerInGame {
+        &mut self.players[seat]
+    }
+
+    /// Seat index of `owner`, or `None` if they are not in this match.
+    pub fn seat_of(&self, owner: &AccountOwner) -> Option<usize> {
+        self.players.iter().position(|p| p.owner == *owner)
+    }
+
+    /// Seats on other teams that are still in the game.
+    pub fn opponent_seats(&self, seat: usize) -> Vec<usize> {
+        let team = self.players[seat].team;
+        self.players
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.team != team && !p.eliminated)
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// Members of the winning team; empty while running or after a draw.
+    pub fn winners(&self) -> Vec<AccountOwner> {
+        let Some(team) = self.winner else {
+            return vec![];
+        };
+        self.players
+            .iter()
+            .filter(|p| p.team == team)
+            .map(|p| p.owner)
+            .collect()
+    }
+
+    /// Final placements as (owner, place), place 1 being the winning team.
+    ///
+    /// Teams still standing after an alternate win come next, then the rest
+    /// by how long they survived. Teammates share a place. Returns the
+    /// placements and the number of distinct places.
+    pub fn standings(&self) -> (Vec<(AccountOwner, u32)>, u32) {
+        let mut team_order: Vec<u8> = self.winner.into_iter().collect();
+        for player in &self.players {
+            if !player.eliminated && !team_order.contains(&player.team) {
+                team_order.push(player.team);
+            }
+        }
+        for owner in self.elimination_order.iter().rev() {
+            if let Some(seat) = self.seat_of(owner) {
+                let team = self.players[seat].team;
+                if !team_order.contains(&team) {
+                    team_order.push(team);
+                }
+            }
+        }
+
+        let placements = self
+            .players
+            .iter()
+            .map(|p| {
+                let place = team_order.iter().position(|t| *t == p.team).unwrap_or(0);
+                (p.owner, place as u32 + 1)
+            })
+            .collect();
+        (placements, team_order.len() as u32)
+    }
+
+    /// Appends an event to the match log, stamped with the current turn.
+    pub fn log(&mut self, mut event: GameEvent) {
+        event.index = self.events.len() as u32;
+        event.turn = self.current_turn;
+        self.events.push(event);
+    }
+
+    /// Moves a card between zones and records the change in the event log.
+    pub fn move_card(
+        &mut self,
+        seat: usize,
+        card_id: u32,
+        from: Zone,
+        to: Zone,
+        cards: &[Card],
+    ) -> Option<ZoneChange> {
+        let change = self.players[seat].move_card(card_id, from, to, cards)?;
+        self.log(change.clone().into());
+        Some(change)
+    }
+
+    /// Moves the creature in one of `seat`'s field slots, logging the move.
+    pub fn move_field_card(&mut self, seat: usize, slot: usize, to: Zone) -> Option<ZoneChange> {
+        let change = self.players[seat].move_field_card(slot, to)?;
+        self.log(change.clone().into());
+        Some(change)
+    }
+
+    /// Draws a card; drawing from an empty deck marks the player as decked out.
+    pub fn draw_card(&mut self, seat: usize, cards: &[Card]) -> Option<ZoneChange> {
+        let Some(&card_id) = self.players[seat].deck.first() else {
+            self.players[seat].decked_out = true;
+            return None;
+        };
+        self.move_card(seat, card_id, Zone::Deck, Zone::Hand, cards)
+    }
+
+    /// Every player draws their opening hand, one card at a time in seat order.
+    pub fn deal_opening_hands(&mut self, cards: &[Card]) {
+        for _ in 0..OPENING_HAND_SIZE {
+            for seat in 0..self.players.len() {
+                self.draw_card(seat, cards);
+            }
+        }
+    }
+
+    /// Eliminates every player who has lost, through health loss from any
+    /// source or by decking out, and ends the match once a single team is
+    /// left. Every action runs this before returning. Returns whether the
+    /// match is over.
+    pub fn check_game_over(&mut self) -> bool {
+        if self.game_phase == GamePhase::Finished {
+            return true;
+        }
+
+        let mut reason = EndReason::Health;
+        for seat in 0..self.players.len() {
+            let player = &self.players[seat];
+            if !player.eliminated && player.has_lost() {
+                reason = if player.health <= 0 {
+                    EndReason::Health
+                } else {
+                    EndReason::DeckOut
+                };
+                self.eliminate(seat);
+            }
+        }
+
+        match self.teams_left().as_slice() {
+            // Everyone left went down at once
+            [] => self.finish(None, reason),
+            [team] => self.finish(Some(*team), reason),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Checks the alternate win conditions of the cards a player controls.
+    /// Runs at the start of that player's turn; a win counts for their team.
+    pub fn check_alternate_wins(&mut self, seat: usize) -> bool {
+        let player = &self.players[seat];
+        let won = player
+            .field
+            .iter()
+            .filter_map(|c| c.win_condition.as_ref())
+            .any(|condition| condition.is_met(player));
+
+        if won {
+            self.finish(Some(player.team), EndReason::AlternateWin);
+        }
+        won
+    }
+
+    /// Whether the active player has been idle for at least `timeout`.
+    pub fn is_abandoned(&self, now: Timestamp, timeout: TimeDelta) -> bool {
+        self.game_phase != GamePhase::Finished && self.turn_timer.saturating_add(timeout) <= now
+    }
+
+    /// Deals with the idle active player of an abandoned match. A human
+    /// forfeits: they are eliminated, and the turn moves on if more than one
+    /// team is still standing. An idle bot, or a match still on its first
+    /// turn, ends as a no-contest. Returns the next player if play goes on.
+    pub fn abandon(
+        &mut self,
+        now: Timestamp,
+        cards: &[Card],
+    ) -> Result<Option<AccountOwner>, String> {
+        if self.game_phase == GamePhase::Finished {
+            return Err("Match is already finished".to_string());
+        }
+
+        let seat = self.active_seat as usize;
+        if self.players[seat].is_bot() || self.current_turn == 1 {
+            self.finish(None, EndReason::NoContest);
+            return Ok(None);
+        }
+
+        self.eliminate(seat);
+        match self.teams_left().as_slice() {
+            [] => self.finish(None, EndReason::Forfeit),
+            [team] => self.finish(Some(*team), EndReason::Forfeit),
+            _ => {
+                self.turn_timer = now;
+                return Ok(Some(self.pass_turn(cards)));
+            }
+        }
+        Ok(None)
+    }
+
+    fn teams_left(&self) -> Vec<u8> {
+        let mut teams: Vec<u8> = self
+            .players
+            .iter()
+            .filter(|p| !p.eliminated)
+            .map(|p| p.team)
+            .collect();
+        teams.sort_unstable();
+        teams.dedup();
+        teams
+    }
+
+    fn eliminate(&mut self, seat: usize) {
+        let player = &mut self.players[seat];
+        player.eliminated = true;
+        let owner = player.owner;
+        self.elimination_order.push(owner);
+        self.log(GameEvent::new(GameEventKind::PlayerEliminated, owner));
+    }
+
+    /// Ends a running match by admin decision; `None` makes it a draw.
+    pub fn force_end(&mut self, winner: Option<u8>) -> Result<(), String> {
+        if self.game_phase == GamePhase::Finished {
+            return Err("Match is already finished".to_string());
+        }
+        if let Some(team) = winner {
+            if !self.players.iter().any(|p| p.team == team) {
+                return Err(format!("No team {} in this match", team));
+            }
+        }
+        self.finish(winner, EndReason::AdminEnded);
+        Ok(())
+    }
+
+    fn finish(&mut self, winner: Option<u8>, reason: EndReason) {
+        self.winner = winner;
+        self.end_reason = Some(reason);
+        self.game_phase = GamePhase::Finished;
+    }
+
+    /// Plays a card from hand, paying its cost.
+    ///
+    /// Creatures stay on the field; everything else resolves and goes to the
+    /// graveyard.
+    pub fn play_card(
+        &mut self,
+        owner: AccountOwner,
+        card_id: u32,
+        cards: &[Card],
+    ) -> Result<Card, String> {
+        let seat = self.active_seat_of(&owner)?;
+        let player = &mut self.players[seat];
+
+        let card = player
+            .hand
+            .iter()
+            .find(|c| c.id == card_id)
+            .cloned()
+            .ok_or("Card is not in hand")?;
+        if player.mana < card.cost {
+            return Err("Not enough mana".to_string());
+        }
+        player.mana -= card.cost;
+
+        self.log(GameEvent::new(GameEventKind::CardPlayed, owner).with_card(card_id));
+        let destination = match card.card_type {
+            CardType::Creature => Zone::Field,
+            _ => Zone::Graveyard,
+        };
+        self.move_card(seat, card_id, Zone::Hand, destination, cards);
+        self.check_game_over();
+
+        Ok(card)
+    }
+
+    /// Passes the turn to the next seat still in the game, which draws a
+    /// card. Returns the next player.
+    pub fn end_turn(
+        &mut self,
+        owner: AccountOwner,
+        now: Timestamp,
+        cards: &[Card],
+    ) -> Result<AccountOwner, String> {
+        self.active_seat_of(&owner)?;
+        self.turn_timer = now;
+        Ok(self.pass_turn(cards))
+    }
+
+    /// Hands the turn to the next seat still in the game, who draws. A
+    /// player who decks out on their draw is eliminated and the turn moves
+    /// on again.
+    fn pass_turn(&mut self, cards: &[Card]) -> AccountOwner {
+        let ending = self.active_seat as usize;
+        self.players[ending].attacked.clear();
+        loop {
+            let seat = self.next_seat(self.active_seat as usize);
+            self.active_seat = seat as u8;
+            self.current_turn += 1;
+
+            let next_player = self.players[seat].owner;
+            self.log(GameEvent::new(GameEventKind::TurnChanged, next_player));
+            self.draw_card(seat, cards);
+
+            if self.check_game_over() {
+                return next_player;
+            }
+            if !self.players[seat].eliminated {
+                self.check_alternate_wins(seat);
+                return next_player;
+            }
+        }
+    }
+
+    /// Attacks an opponent directly with a creature on the field.
+    pub fn attack_player(
+        &mut self,
+        owner: AccountOwner,
+        attacker_id: u32,
+        target: Option<AccountOwner>,
+    ) -> Result<(), String> {
+        let seat = self.active_seat_of(&owner)?;
+        let target_seat = self.target_seat(seat, target)?;
+        let slot = self.players[seat].ready_attacker(attacker_id)?;
+        let attacker = self.players[seat].field[slot].clone();
+
+        self.players[seat].mark_attacked(slot);
+        let damage = attacker.attack as i32;
+        let opponent = &mut self.players[target_seat];
+        opponent.health -= damage;
+        let target = opponent.owner;
+
+        self.log(
+            GameEvent::new(GameEventKind::Attack, owner)
+                .with_card(attacker_id)
+                .with_target_player(target),
+        );
+        self.log(
+            GameEvent::new(GameEventKind::Damage, owner)
+                .with_card(attacker_id)
+                .with_target_player(target)
+                .with_amount(damage),
+        );
+        self.check_game_over();
+
+        Ok(())
+    }
+
+    /// Fights two creatures; each one whose defense is overcome dies.
+    pub fn attack_creature(
+        &mut self,
+        owner: AccountOwner,
+        attacker_id: u32,
+        defender_id: u32,
+        target: Option<AccountOwner>,
+        cards: &[Card],
+    ) -> Result<(), String> {
+        let seat = self.active_seat_of(&owner)?;
+        let target_seat = self.target_seat(seat, target)?;
+        let slot = self.players[seat].ready_attacker(attacker_id)?;
+        let attacker = self.players[seat].field[slot].clone();
+        let defending_player = &self.players[target_seat];
+        let defender = defending_player
+            .field
+            .iter()
+            .find(|c| c.id == defender_id)
+            .cloned()
+            .ok_or("Defender is not on the field")?;
+        let defender_owner = defending_player.owner;
+        self.players[seat].mark_attacked(slot);
+
+        self.log(
+            GameEvent::new(GameEventKind::Attack, owner)
+                .with_card(attacker_id)
+                .with_target_player(defender_owner)
+                .with_target_card(defender_id),
+        );
+        self.log(
+            GameEvent::new(GameEventKind::Damage, owner)
+                .with_card(attacker_id)
+                .with_target_card(defender_id)
+                .with_amount(attacker.attack as i32),
+        );
+        self.log(
+            GameEvent::new(GameEventKind::Damage, defender_owner)
+                .with_card(defender_id)
+                .with_target_card(attacker_id)
+                .with_amount(defender.attack as i32),
+        );
+
+        // Destroyed creatures go to their owner's graveyard
+        if attacker.attack >= defender.defense {
+            self.move_card(target_seat, defender_id, Zone::Field, Zone::Graveyard, cards);
+        }
+        if defender.attack >= attacker.defense {
+            self.move_field_card(seat, slot, Zone::Graveyard);
+        }
+        self.check_game_over();
+
+        Ok(())
+    }
+
+    /// Casts a counter from hand at instant speed, on any player's turn.
+    pub fn instant_counter(
+        &mut self,
+        owner: AccountOwner,
+        card_id: u32,
+        target_card: u32,
+        cards: &[Card],
+    ) -> Result<Card, String> {
+        if self.game_phase == GamePhase::Finished {
+            return Err("Match is already finished".to_string());
+        }
+        let seat = self.seat_of(&owner).ok_or("Not a player in this match")?;
+        if self.players[seat].eliminated {
+            return Err("Player has been eliminated".to_string());
+        }
+        if !self.counter_targets(seat).contains(&target_card) {
+            return Err("Target is not an enemy creature on the field".to_string());
+        }
+
+        let player = &mut self.players[seat];
+        let card = player
+            .hand
+            .iter()
+            .find(|c| c.id == card_id)
+            .cloned()
+            .ok_or("Card is not in hand")?;
+        if !matches!(card.card_type, CardType::Counter) {
+            return Err("Card is not a counter".to_string());
+        }
+        if player.mana < card.cost {
+            return Err("Not enough mana".to_string());
+        }
+        player.mana -= card.cost;
+
+        self.log(
+            GameEvent::new(GameEventKind::CounterActivated, owner)
+                .with_card(card_id)
+                .with_target_card(target_card),
+        );
+        self.move_card(seat, card_id, Zone::Hand, Zone::Graveyard, cards);
+        self.check_game_over();
+
+        Ok(card)
+    }
+
+    /// Performs one action on behalf of `owner`.
+    pub fn apply_action(
+        &mut self,
+        owner: AccountOwner,
+        action: &GameAction,
+        now: Timestamp,
+        cards: &[Card],
+    ) -> Result<(), String> {
+        match action {
+            GameAction::PlayCard { card_id } => {
+                self.play_card(owner, *card_id, cards)?;
+            }
+            GameAction::InstantCounter {
+                card_id,
+                target_card,
+            } => {
+                self.instant_counter(owner, *card_id, *target_card, cards)?;
+            }
+            GameAction::AttackPlayer {
+                attacker_id,
+                target,
+            } => self.attack_player(owner, *attacker_id, *target)?,
+            GameAction::AttackCreature {
+                attacker_id,
+                defender_id,
+                target,
+            } => self.attack_creature(owner, *attacker_id, *defender_id, *target, cards)?,
+            GameAction::EndTurn => {
+                self.end_turn(owner, now, cards)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Every action `seat` may take right now. Counters can be cast on any
+    /// turn; everything else only on the player's own turn.
+    pub fn legal_actions(&self, seat: usize) -> Vec<GameAction> {
+        let player = &self.players[seat];
+        if self.game_phase == GamePhase::Finished || player.eliminated {
+            return vec![];
+        }
+        let opponents = self.opponent_seats(seat);
+        let counter_targets = self.counter_targets(seat);
+
+        let mut actions = vec![];
+        for card in player.hand.iter().filter(|c| c.cost <= player.mana) {
+            if matches!(card.card_type, CardType::Counter) {
+                for target_card in &counter_targets {
+                    actions.push(GameAction::InstantCounter {
+                        card_id: card.id,
+                        target_card: *target_card,
+                    });
+                }
+            } else if seat == self.active_seat as usize {
+                actions.push(GameAction::PlayCard { card_id: card.id });
+            }
+        }
+        if seat != self.active_seat as usize {
+            return actions;
+        }
+
+        // Targets are always named so the actions stay valid with several opponents
+        // Copies of a card share one set of actions, taken by the first ready copy
+        let mut attackers: Vec<u32> = vec![];
+        for slot in (0..player.field.len()).filter(|slot| !player.has_attacked(*slot)) {
+            if !attackers.contains(&player.field[slot].id) {
+                attackers.push(player.field[slot].id);
+            }
+        }
+        for attacker_id in attackers {
+            for opponent in &opponents {
+                let target = Some(self.players[*opponent].owner);
+                actions.push(GameAction::AttackPlayer {
+                    attacker_id,
+                    target,
+                });
+                for defender in &self.players[*opponent].field {
+                    actions.push(GameAction::AttackCreature {
+                        attacker_id,
+                        defender_id: defender.id,
+                        target,
+                    });
+                }
+            }
+        }
+        actions.push(GameAction::EndTurn);
+        actions
+    }
+
+    /// Creatures `seat` may counter: each card on an opponent's field, once.
+    fn counter_targets(&self, seat: usize) -> Vec<u32> {
+        let mut targets = vec![];
+        for opponent in self.opponent_seats(seat) {
+            for creature in &self.players[opponent].field {
+                if !targets.contains(&creature.id) {
+                    targets.push(creature.id);
+                }
+            }
+        }
+        targets
+    }
+
+    /// Checks the match is running and it's `owner`'s turn, returning their seat.
+    fn active_seat_of(&self, owner: &AccountOwner) -> Result<usize, String> {
+        if self.game_phase == GamePhase::Finished {
+            return Err("Match is already finished".to_string());
+        }
+        let seat = self.seat_of(owner).ok_or("Not a player in this match")?;
+        if seat != self.active_seat as usize {
+            return Err("Not your turn".to_string());
+        }
+        Ok(seat)
+    }
+
+    /// Resolves an attack target: any opponent still in the game, which may
+    /// be left implicit when only one remains.
+    fn target_seat(&self, seat: usize, target: Option<AccountOwner>) -> Result<usize, String> {
+        let opponents = self.opponent_seats(seat);
+        match target {
+            Some(target) => {
+                let target_seat = self.seat_of(&target).ok_or("Target is not in this match")?;
+                if !opponents.contains(&target_seat) {
+                    return Err("Target is not an opponent still in the game".to_string());
+                }
+                Ok(target_seat)
+            }
+            None => match opponents.as_slice() {
+                [only] => Ok(*only),
+                _ => Err("Choose which opponent to target".to_string()),
+            },
+        }
+    }
+
+    /// The next seat after `seat`, clockwise, that has not been eliminated.
+    fn next_seat(&self, seat: usize) -> usize {
+        let count = self.players.len();
+        (1..=count)
+            .map(|offset| (seat + offset) % count)
+            .find(|next| !self.players[*next].eliminated)
+            .unwrap_or(seat)
+    }
+}
+
+impl MatchRecord {
+    pub fn new(game: GameState, ended_at: Timestamp) -> Self {
+        MatchRecord {
+            match_id: game.match_id,
+            series_id: game.series_id,
+            mode: game.mode,
+            format: game.format,
+            players: game.players.iter().map(|p| p.owner).collect(),
+            decks: game.players.iter().map(|p| p.decklist()).collect(),
+            winner: game.winner,
+            winners: game.winners(),
+            turns: game.current_turn,
+            started_at: game.started_at,
+            ended_at,
+            end_reason: game.end_reason,
+            final_state: game,
+        }
+    }
+
+    /// How the match ended for `owner`.
+    pub fn result_for(&self, owner: &AccountOwner) -> MatchResult {
+        if self.winners.is_empty() {
+            MatchResult::Draw
+        } else if self.winners.contains(owner) {
+            MatchResult::Win
+        } else {
+            MatchResult::Loss
+        }
+    }
+
+    /// Whether `other` played on a different team from `owner`.
+    pub fn is_opponent(&self, owner: &AccountOwner, other: &AccountOwner) -> bool {
+        let game = &self.final_state;
+        match (game.seat_of(owner), game.seat_of(other)) {
+            (Some(seat), Some(other_seat)) => {
+                game.players[seat].team != game.players[other_seat].team
+            }
+            _ => false,
+        }
+    }
+}
+
+fn take_by_id(cards: &mut Vec<Card>, card_id: u32) -> Option<Card> {
+    let idx = cards.iter().position(|c| c.id == card_id)?;
+    Some(cards.remove(idx))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::create_starter_deck;
+
+    /// 4/2 creature.
+    const STRIKER: u32 = 3;
+    /// 2/5 creature.
+    const WALL: u32 = 2;
+    /// Counter costing 2.
+    const NULLIFY: u32 = 4;
+
+    fn owner(n: u8) -> AccountOwner {
+        AccountOwner::Address20([n; 20])
+    }
+
+    /// A match between one player per team, seat 0 to act, every deck
+    /// holding `deck`.
+    fn game(teams: &[u8], deck: &[u32]) -> GameState {
+        let players = teams
+            .iter()
+            .enumerate()
+            .map(|(seat, team)| {
+                PlayerInGame::new(owner(seat as u8 + 1), seat as u8, *team, deck.to_vec())
+            })
+            .collect();
+        let mode = if teams.len() == 2 {
+            MatchMode::Duel
+        } else {
+            MatchMode::FreeForAll
+        };
+        GameState::new(1, None, mode, DeckFormat::Constructed, players, Timestamp::from(0))
+    }
+
+    fn card(card_id: u32) -> Card {
+        find_card(&create_starter_deck(), card_id).unwrap()
+    }
+
+    #[test]
+    fn each_copy_of_a_creature_attacks_once() {
+        let mut game = game(&[0, 1], &[]);
+        game.players[0].field = vec![card(STRIKER), card(STRIKER)];
+
+        game.attack_player(owner(1), STRIKER, None).unwrap();
+        assert!(game
+            .legal_actions(0)
+            .iter()
+            .any(|action| matches!(action, GameAction::AttackPlayer { .. })));
+        game.attack_player(owner(1), STRIKER, None).unwrap();
+
+        assert_eq!(game.players[1].health, STARTING_HEALTH - 8);
+        assert!(game.attack_player(owner(1), STRIKER, None).is_err());
+        assert_eq!(game.legal_actions(0), vec![GameAction::EndTurn]);
+    }
+
+    #[test]
+    fn a_new_copy_can_attack_after_the_first_dies() {
+        let cards = create_starter_deck();
+        let mut game = game(&[0, 1], &[]);
+        game.players[0].field = vec![card(STRIKER)];
+        game.players[0].hand = vec![card(STRIKER)];
+        game.players[1].field = vec![card(WALL)];
+
+        game.attack_creature(owner(1), STRIKER, WALL, None, &cards).unwrap();
+        assert!(game.players[0].field.is_empty());
+
+        game.players[0].move_card(STRIKER, Zone::Hand, Zone::Field, &cards);
+        game.attack_player(owner(1), STRIKER, None).unwrap();
+    }
+
+    #[test]
+    fn attacks_reset_when_the_turn_passes() {
+        let cards = create_starter_deck();
+        let mut game = game(&[0, 1], &[STRIKER, STRIKER]);
+        game.players[0].field = vec![card(STRIKER)];
+
+        game.attack_player(owner(1), STRIKER, None).unwrap();
+        game.end_turn(owner(1), Timestamp::from(0), &cards).unwrap();
+        game.end_turn(owner(2), Timestamp::from(0), &cards).unwrap();
+        game.attack_player(owner(1), STRIKER, None).unwrap();
+    }
+
+    #[test]
+    fn counters_need_an_enemy_creature_as_target() {
+        let cards = create_starter_deck();
+        let mut game = game(&[0, 1], &[]);
+        game.players[1].hand = vec![card(NULLIFY)];
+        game.players[1].mana = 2;
+        game.players[0].field = vec![card(STRIKER)];
+        game.players[1].field = vec![card(WALL)];
+
+        let counters: Vec<GameAction> = game
+            .legal_actions(1)
+            .into_iter()
+            .filter(|action| matches!(action, GameAction::InstantCounter { .. }))
+            .collect();
+        assert_eq!(
+            counters,
+            vec![GameAction::InstantCounter {
+                card_id: NULLIFY,
+                target_card: STRIKER,
+            }]
+        );
+
+        for target_card in [WALL, 99] {
+            assert!(game
+                .instant_counter(owner(2), NULLIFY, target_card, &cards)
+                .is_err());
+        }
+        assert_eq!(game.players[1].mana, 2);
+        game.instant_counter(owner(2), NULLIFY, STRIKER, &cards).unwrap();
+        assert_eq!(game.players[1].mana, 0);
+    }
+
+    #[test]
+    fn decking_out_eliminates_the_player() {
+        let mut game = game(&[0, 1], &[]);
+        game.end_turn(owner(1), Timestamp::from(0), &[]).unwrap();
+
+        assert!(game.players[1].eliminated);
+        assert_eq!(game.game_phase, GamePhase::Finished);
+        assert_eq!(game.winner, Some(0));
+        assert_eq!(game.end_reason, Some(EndReason::DeckOut));
+    }
+
+    #[test]
+    fn turns_pass_clockwise_and_skip_eliminated_seats() {
+        let cards = create_starter_deck();
+        let mut game = game(&[0, 1, 2], &[WALL, WALL, WALL]);
+        assert_eq!(game.end_turn(owner(1), Timestamp::from(0), &cards), Ok(owner(2)));
+        assert_eq!(game.end_turn(owner(2), Timestamp::from(0), &cards), Ok(owner(3)));
+        assert_eq!(game.end_turn(owner(3), Timestamp::from(0), &cards), Ok(owner(1)));
+        assert!(game.end_turn(owner(3), Timestamp::from(0), &cards).is_err());
+
+        game.players[1].health = 0;
+        game.check_game_over();
+        assert_eq!(game.game_phase, GamePhase::InProgress);
+        assert_eq!(game.end_turn(owner(1), Timestamp::from(0), &cards), Ok(owner(3)));
+    }
+
+    #[test]
+    fn decking_out_mid_match_passes_the_turn_on() {
+        let cards = create_starter_deck();
+        let mut game = game(&[0, 1, 2], &[WALL, WALL]);
+        game.players[1].deck.clear();
+
+        assert_eq!(game.end_turn(owner(1), Timestamp::from(0), &cards), Ok(owner(3)));
+        assert!(game.players[1].eliminated);
+        assert_eq!(game.active_seat, 2);
+        assert_eq!(game.game_phase, GamePhase::InProgress);
+        assert_eq!(game.elimination_order, vec![owner(2)]);
+    }
+
+    #[test]
+    fn alternate_wins_are_checked_when_the_turn_starts() {
+        let cards = create_starter_deck();
+        let mut totem = card(WALL);
+        totem.win_condition = Some(WinCondition {
+            kind: WinConditionKind::ControlCreatures,
+            threshold: 2,
+        });
+        let mut game = game(&[0, 1], &[WALL, WALL]);
+        game.players[1].field = vec![totem.clone()];
+
+        game.end_turn(owner(1), Timestamp::from(0), &cards).unwrap();
+        game.end_turn(owner(2), Timestamp::from(0), &cards).unwrap();
+        assert_eq!(game.game_phase, GamePhase::InProgress);
+
+        game.players[1].field.push(card(STRIKER));
+        game.end_turn(owner(1), Timestamp::from(0), &cards).unwrap();
+        assert_eq!(game.game_phase, GamePhase::Finished);
+        assert_eq!(game.winner, Some(1));
+        assert_eq!(game.end_reason, Some(EndReason::AlternateWin));
+    }
+}
@@ -0,0 +1,262 @@
+use crate::{
+    Card, CardType, DeckFormat, GamePhase, GameState, MatchMode, PlayerInGame, PlayerStats,
+    PveStats, Rarity, VersionedGameState, VersionedPlayerStats,
+};
+use linera_sdk::base::{AccountOwner, Timestamp};
+use serde::{Deserialize, Serialize};
+
+/// Layout version of the records this build writes.
+///
+/// 0: the original two-player layout: untagged `PlayerStatsV0` and
+///    `GameStateV0` records, one match per player and the cards in a single
+///    list. These views keep their original positions as the `legacy_*`
+///    fields.
+/// 1: records tagged with `VersionedPlayerStats` and `VersionedGameState`.
+/// 2: every profile is listed in the ranking index.
+/// 3: every active match is listed in the turn timer index.
+pub const SCHEMA_VERSION: u32 = 3;
+
+/// Records each operation migrates while a migration is pending.
+pub const MIGRATION_BATCH_SIZE: u32 = 20;
+
+// The layouts below are frozen: they must keep decoding what schema 0 wrote.
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CardV0 {
+    pub id: u32,
+    pub name: String,
+    pub description: String,
+    pub attack: u32,
+    pub defense: u32,
+    pub cost: u32,
+    pub card_type: CardTypeV0,
+    pub ability: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CardTypeV0 {
+    Creature,
+    Spell,
+    Counter,
+    Buff,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerStatsV0 {
+    pub owner: AccountOwner,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    pub ranking: u32,
+    pub total_matches: u32,
+    pub cards_owned: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameStateV0 {
+    pub match_id: u64,
+    pub player1: PlayerInGameV0,
+    pub player2: PlayerInGameV0,
+    pub current_turn: u8,
+    pub turn_timer: Timestamp,
+    pub game_phase: GamePhaseV0,
+    /// 1 or 2 for `player1` or `player2`.
+    pub winner: Option<u8>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerInGameV0 {
+    pub owner: AccountOwner,
+    pub health: i32,
+    pub mana: u32,
+    pub hand: Vec<CardV0>,
+    pub deck: Vec<u32>,
+    pub field: Vec<CardV0>,
+    pub graveyard: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GamePhaseV0 {
+    WaitingForPlayers,
+    Player1Turn,
+    Player2Turn,
+    Finished,
+}
+
+impl From<CardV0> for Card {
+    fn from(card: CardV0) -> Self {
+        Card {
+            id: card.id,
+            name: card.name,
+            description: card.description,
+            attack: card.attack,
+            defense: card.defense,
+            cost: card.cost,
+            card_type: match card.card_type {
+                CardTypeV0::Creature => CardType::Creature,
+                CardTypeV0::Spell => CardType::Spell,
+                CardTypeV0::Counter => CardType::Counter,
+                CardTypeV0::Buff => CardType::Buff,
+            },
+            rarity: Rarity::Common,
+            ability: card.ability,
+            win_condition: None,
+        }
+    }
+}
+
+impl From<PlayerStatsV0> for PlayerStats {
+    fn from(stats: PlayerStatsV0) -> Self {
+        PlayerStats {
+            owner: stats.owner,
+            wins: stats.wins,
+            losses: stats.losses,
+            draws: stats.draws,
+            ranking: stats.ranking,
+            total_matches: stats.total_matches,
+            cards_owned: stats.cards_owned,
+            puzzles_solved: vec![],
+            pve: PveStats::default(),
+        }
+    }
+}
+
+impl PlayerInGameV0 {
+    /// Seats the player, alone on their team.
+    fn upgrade(self, seat: u8) -> PlayerInGame {
+        let mut player = PlayerInGame::new(self.owner, seat, seat, self.deck);
+        player.health = self.health;
+        player.mana = self.mana;
+        player.hand = self.hand.into_iter().map(Card::from).collect();
+        player.field = self.field.into_iter().map(Card::from).collect();
+        player.graveyard = self.graveyard;
+        player
+    }
+}
+
+impl From<GameStateV0> for GameState {
+    fn from(game: GameStateV0) -> Self {
+        let players = vec![game.player1.upgrade(0), game.player2.upgrade(1)];
+        let mut state = GameState::new(
+            game.match_id,
+            None,
+            MatchMode::Duel,
+            DeckFormat::Constructed,
+            players,
+            game.turn_timer,
+        );
+        state.active_seat = u8::from(game.game_phase == GamePhaseV0::Player2Turn);
+        state.current_turn = game.current_turn;
+        // Schema 0 matches stayed `WaitingForPlayers` while being played
+        state.game_phase = match game.game_phase {
+            GamePhaseV0::Finished => GamePhase::Finished,
+            _ => GamePhase::InProgress,
+        };
+        state.winner = game.winner.map(|player| player.saturating_sub(1));
+        state
+    }
+}
+
+impl VersionedPlayerStats {
+    /// Converts a record of any layout to the current one.
+    pub fn upgrade(self) -> PlayerStats {
+        match self {
+            VersionedPlayerStats::V1(stats) => stats,
+        }
+    }
+}
+
+impl From<PlayerStats> for VersionedPlayerStats {
+    fn from(stats: PlayerStats) -> Self {
+        VersionedPlayerStats::V1(stats)
+    }
+}
+
+impl VersionedGameState {
+    /// Converts a record of any layout to the current one.
+    pub fn upgrade(self) -> GameState {
+        match self {
+            VersionedGameState::V1(game) => game,
+        }
+    }
+}
+
+impl From<GameState> for VersionedGameState {
+    fn from(game: GameState) -> Self {
+        VersionedGameState::V1(game)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use linera_sdk::bcs;
+
+    /// A schema 0 card, field by field: id, name, description, attack,
+    /// defense, cost, card type variant and ability.
+    type CardBytes = (u32, String, String, u32, u32, u32, u8, Option<String>);
+
+    fn card(id: u32, card_type: u8) -> CardBytes {
+        let name = format!("Card {}", id);
+        (id, name, String::new(), 2, 3, 1, card_type, None)
+    }
+
+    #[test]
+    fn decodes_schema_0_profile() {
+        let owner = AccountOwner::Address20([7; 20]);
+        let bytes = bcs::to_bytes(&(owner, 3u32, 1u32, 2u32, 1216u32, 6u32, vec![1u32, 2, 2]))
+            .unwrap();
+
+        let stats = PlayerStats::from(bcs::from_bytes::<PlayerStatsV0>(&bytes).unwrap());
+        assert_eq!(stats.owner, owner);
+        assert_eq!((stats.wins, stats.losses, stats.draws), (3, 1, 2));
+        assert_eq!((stats.ranking, stats.total_matches), (1216, 6));
+        assert_eq!(stats.cards_owned, vec![1, 2, 2]);
+        assert!(stats.puzzles_solved.is_empty());
+        assert_eq!(stats.pve.wins + stats.pve.losses + stats.pve.draws, 0);
+    }
+
+    #[test]
+    fn decodes_schema_0_match() {
+        let player1 = AccountOwner::Address20([1; 20]);
+        let player2 = AccountOwner::Address20([2; 20]);
+        let seat = |owner, health| {
+            let field: Vec<CardBytes> = vec![card(6, 0)];
+            (owner, health, 4u32, vec![card(4, 2)], vec![5u32, 7], field, vec![9u32])
+        };
+        // Player 2 to move, no winner yet
+        let bytes = bcs::to_bytes(&(
+            11u64,
+            seat(player1, 20i32),
+            seat(player2, 17i32),
+            5u8,
+            Timestamp::from(1_000),
+            2u8,
+            None::<u8>,
+        ))
+        .unwrap();
+
+        let mut legacy = bcs::from_bytes::<GameStateV0>(&bytes).unwrap();
+        let game = GameState::from(legacy.clone());
+        assert_eq!(game.match_id, 11);
+        assert_eq!(game.active_seat, 1);
+        assert_eq!(game.current_turn, 5);
+        assert_eq!(game.game_phase, GamePhase::InProgress);
+        assert_eq!(game.winner, None);
+        assert_eq!(game.players.len(), 2);
+
+        let second = &game.players[1];
+        assert_eq!((second.owner, second.seat, second.team), (player2, 1, 1));
+        assert_eq!((second.health, second.mana), (17, 4));
+        assert_eq!(second.hand[0].id, 4);
+        assert!(matches!(second.hand[0].card_type, CardType::Counter));
+        assert_eq!(second.field[0].id, 6);
+        assert_eq!(second.deck, vec![5, 7]);
+        assert_eq!(second.graveyard, vec![9]);
+
+        // Schema 0 numbered the winner from 1; teams are numbered from 0
+        legacy.game_phase = GamePhaseV0::Finished;
+        legacy.winner = Some(2);
+        assert_eq!(GameState::from(legacy).winner, Some(1));
+    }
+}
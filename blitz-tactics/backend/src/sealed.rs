@@ -0,0 +1,138 @@
+use crate::{rng::MatchRng, Card, Rarity, SealedPool};
+use linera_sdk::base::AccountOwner;
+
+/// Cards in a freshly opened sealed pool.
+pub const SEALED_POOL_SIZE: usize = 20;
+/// Cards in a deck built from a sealed pool.
+pub const SEALED_DECK_SIZE: usize = 10;
+
+/// Relative odds of each rarity when filling a pool slot.
+const RARITY_WEIGHTS: [(Rarity, u32); 4] = [
+    (Rarity::Common, 60),
+    (Rarity::Uncommon, 25),
+    (Rarity::Rare, 12),
+    (Rarity::Legendary, 3),
+];
+
+/// Rolls a sealed pool. Each slot rolls a rarity by weight and then a card
+/// of that rarity; if the database has none, any card fills the slot.
+pub fn generate_pool(cards: &[Card], rng: &mut MatchRng) -> Vec<u32> {
+    if cards.is_empty() {
+        return vec![];
+    }
+    let total: u32 = RARITY_WEIGHTS.iter().map(|(_, weight)| weight).sum();
+
+    (0..SEALED_POOL_SIZE)
+        .map(|_| {
+            let mut roll = rng.below(total);
+            let rarity = RARITY_WEIGHTS
+                .iter()
+                .find(|(_, weight)| {
+                    if roll < *weight {
+                        return true;
+                    }
+                    roll -= weight;
+                    false
+                })
+                .map(|(rarity, _)| *rarity)
+                .unwrap_or(Rarity::Common);
+
+            let candidates: Vec<u32> = cards
+                .iter()
+                .filter(|c| c.rarity == rarity)
+                .map(|c| c.id)
+                .collect();
+            if candidates.is_empty() {
+                cards[rng.below(cards.len() as u32) as usize].id
+            } else {
+                candidates[rng.below(candidates.len() as u32) as usize]
+            }
+        })
+        .collect()
+}
+
+impl SealedPool {
+    pub fn new(owner: AccountOwner, pool: Vec<u32>) -> Self {
+        SealedPool {
+            owner,
+            pool,
+            deck: vec![],
+        }
+    }
+
+    /// Replaces the deck after checking every card comes from the pool, with
+    /// no card used more often than it was opened.
+    pub fn build_deck(&mut self, cards: Vec<u32>) -> Result<(), String> {
+        if cards.len() != SEALED_DECK_SIZE {
+            return Err(format!(
+                "Sealed decks need exactly {} cards",
+                SEALED_DECK_SIZE
+            ));
+        }
+
+        let mut remaining = self.pool.clone();
+        for card_id in &cards {
+            let idx = remaining
+                .iter()
+                .position(|id| id == card_id)
+                .ok_or_else(|| format!("Card {} is not available in the pool", card_id))?;
+            remaining.swap_remove(idx);
+        }
+
+        self.deck = cards;
+        Ok(())
+    }
+
+    pub fn has_deck(&self) -> bool {
+        self.deck.len() == SEALED_DECK_SIZE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::create_starter_deck;
+
+    /// A pool holding cards 1 to 10 once each, plus a second copy of card 1.
+    fn pool() -> SealedPool {
+        let mut pool: Vec<u32> = (1..=10).collect();
+        pool.push(1);
+        SealedPool::new(AccountOwner::Address20([1; 20]), pool)
+    }
+
+    #[test]
+    fn pools_are_filled_from_the_card_database() {
+        let cards = create_starter_deck();
+        let pool = generate_pool(&cards, &mut MatchRng::from_seed(7));
+        assert_eq!(pool.len(), SEALED_POOL_SIZE);
+        assert!(pool.iter().all(|id| cards.iter().any(|c| c.id == *id)));
+    }
+
+    #[test]
+    fn decks_must_come_from_the_pool() {
+        let mut pool = pool();
+        assert!(pool.build_deck((1..=9).collect()).is_err());
+
+        let mut deck: Vec<u32> = (2..=10).collect();
+        deck.push(11);
+        assert!(pool.build_deck(deck).is_err());
+        assert!(!pool.has_deck());
+
+        pool.build_deck((1..=10).collect()).unwrap();
+        assert!(pool.has_deck());
+    }
+
+    #[test]
+    fn cards_are_used_at_most_as_often_as_opened() {
+        let mut pool = pool();
+        let mut deck = vec![1, 1];
+        deck.extend(3..=10);
+        pool.build_deck(deck.clone()).unwrap();
+        assert_eq!(pool.deck, deck);
+
+        let mut deck = vec![1, 1, 1];
+        deck.extend(4..=10);
+        assert!(pool.build_deck(deck).is_err());
+        assert_eq!(pool.deck[..2], [1, 1]);
+    }
+}
@@ -0,0 +1,149 @@
+use crate::{Series, SeriesLength};
+use linera_sdk::base::AccountOwner;
+
+impl SeriesLength {
+    /// Maximum number of games in the series.
+    pub fn games(&self) -> u8 {
+        match self {
+            SeriesLength::Bo1 => 1,
+            SeriesLength::Bo3 => 3,
+            SeriesLength::Bo5 => 5,
+        }
+    }
+
+    pub fn wins_needed(&self) -> u8 {
+        self.games() / 2 + 1
+    }
+}
+
+impl Series {
+    pub fn new(
+        series_id: u64,
+        player1: AccountOwner,
+        player2: AccountOwner,
+        length: SeriesLength,
+    ) -> Self {
+        Series {
+            series_id,
+            player1,
+            player2,
+            length,
+            player1_wins: 0,
+            player2_wins: 0,
+            games_played: 0,
+            match_ids: vec![],
+            no_contest: false,
+        }
+    }
+
+    /// Records the result of a finished game; `None` is a drawn game.
+    pub fn record_game(&mut self, winner: Option<AccountOwner>) {
+        self.games_played += 1;
+        if winner == Some(self.player1) {
+            self.player1_wins += 1;
+        } else if winner == Some(self.player2) {
+            self.player2_wins += 1;
+        }
+    }
+
+    /// The series ends once someone reaches the wins needed, when every
+    /// game has been played (drawn games count towards the length), or when
+    /// a game ends as a no-contest.
+    pub fn is_over(&self) -> bool {
+        let needed = self.length.wins_needed();
+        self.no_contest
+            || self.player1_wins >= needed
+            || self.player2_wins >= needed
+            || self.games_played >= self.length.games()
+    }
+
+    /// The series winner, or `None` while undecided or for a drawn or
+    /// no-contest series.
+    pub fn winner(&self) -> Option<AccountOwner> {
+        if !self.is_over() || self.no_contest || self.player1_wins == self.player2_wins {
+            return None;
+        }
+        if self.player1_wins > self.player2_wins {
+            Some(self.player1)
+        } else {
+            Some(self.player2)
+        }
+    }
+
+    /// Seats for the next game as (first to act, second); the first player
+    /// alternates every game.
+    pub fn next_seats(&self) -> (AccountOwner, AccountOwner) {
+        if self.games_played % 2 == 0 {
+            (self.player1, self.player2)
+        } else {
+            (self.player2, self.player1)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: AccountOwner = AccountOwner::Address20([1; 20]);
+    const BOB: AccountOwner = AccountOwner::Address20([2; 20]);
+
+    #[test]
+    fn first_to_the_wins_needed_takes_the_series() {
+        let mut series = Series::new(1, ALICE, BOB, SeriesLength::Bo3);
+        series.record_game(Some(ALICE));
+        series.record_game(Some(BOB));
+        assert!(!series.is_over());
+        assert_eq!(series.winner(), None);
+
+        series.record_game(Some(BOB));
+        assert!(series.is_over());
+        assert_eq!(series.winner(), Some(BOB));
+        assert_eq!((series.player1_wins, series.player2_wins), (1, 2));
+    }
+
+    #[test]
+    fn a_series_ends_early_once_decided() {
+        let mut series = Series::new(1, ALICE, BOB, SeriesLength::Bo5);
+        for _ in 0..3 {
+            series.record_game(Some(ALICE));
+        }
+        assert!(series.is_over());
+        assert_eq!(series.games_played, 3);
+        assert_eq!(series.winner(), Some(ALICE));
+    }
+
+    #[test]
+    fn drawn_games_count_towards_the_length() {
+        let mut series = Series::new(1, ALICE, BOB, SeriesLength::Bo3);
+        series.record_game(None);
+        series.record_game(Some(ALICE));
+        series.record_game(None);
+        assert!(series.is_over());
+        assert_eq!(series.winner(), Some(ALICE));
+
+        let mut series = Series::new(2, ALICE, BOB, SeriesLength::Bo1);
+        series.record_game(None);
+        assert!(series.is_over());
+        assert_eq!(series.winner(), None);
+    }
+
+    #[test]
+    fn a_no_contest_ends_the_series_without_a_winner() {
+        let mut series = Series::new(1, ALICE, BOB, SeriesLength::Bo3);
+        series.record_game(Some(ALICE));
+        series.no_contest = true;
+        assert!(series.is_over());
+        assert_eq!(series.winner(), None);
+    }
+
+    #[test]
+    fn the_first_seat_alternates_every_game() {
+        let mut series = Series::new(1, ALICE, BOB, SeriesLength::Bo5);
+        assert_eq!(series.next_seats(), (ALICE, BOB));
+        series.record_game(Some(ALICE));
+        assert_eq!(series.next_seats(), (BOB, ALICE));
+        series.record_game(None);
+        assert_eq!(series.next_seats(), (ALICE, BOB));
+    }
+}
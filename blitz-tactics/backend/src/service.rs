@@ -1,7 +1,17 @@
 use async_graphql::{Context, EmptySubscription, Object, Request, Response, Schema};
 use async_trait::async_trait;
-use blitz_tactics::{Card, GameState, PlayerStats};
-use linera_sdk::{base::AccountOwner, Service, ServiceRuntime};
+use blitz_tactics::{
+    ai::{suggest_move, validate_plan},
+    AiMoveRequest, AiMoveResponse, AuditEntry, Card, DraftRun, GameAction, GameEvent, GameState,
+    LeaderboardEntry, LegalAction, MatchMode, MatchRecord, MatchResult, MoveSuggestion,
+    Operation, Parameters, PlayerStats, Puzzle, SealedPool, Series, Zone,
+};
+use linera_sdk::{base::AccountOwner, http, Service, ServiceRuntime};
+
+/// Page size used when a paginated query doesn't specify `first`.
+const DEFAULT_PAGE_SIZE: u32 = 50;
+/// Upper bound on `first` for paginated queries.
+const MAX_PAGE_SIZE: u32 = 200;
 
 pub struct BlitzTacticsService {
     state: blitz_tactics::state::BlitzTacticsState,
@@ -20,12 +30,15 @@ impl With<ServiceRuntime<Self>> for BlitzTacticsService {
 
 #[async_trait]
 impl Service for BlitzTacticsService {
-    type Parameters = ();
+    type Parameters = Parameters;
 
     async fn handle_query(&self, request: Request) -> Response {
         let schema = Schema::build(
             QueryRoot { state: &self.state },
-            MutationRoot { state: &self.state },
+            MutationRoot {
+                state: &self.state,
+                runtime: &self.runtime,
+            },
             EmptySubscription,
         )
         .finish();
@@ -48,17 +61,23 @@ impl<'a> QueryRoot<'a> {
 
     /// Get all available cards
     async fn card_database(&self) -> Vec<Card> {
-        self.state.card_database.get().clone()
+        self.state.get_all_cards().await
+    }
+
+    /// Version of the card set, bumped whenever a card is added or changed
+    async fn card_set_version(&self) -> u32 {
+        *self.state.card_set_version.get()
+    }
+
+    /// Layout version of the stored records; behind the current one while a
+    /// migration is pending
+    async fn schema_version(&self) -> u32 {
+        *self.state.schema_version.get()
     }
 
     /// Get a specific card by ID
     async fn card(&self, id: u32) -> Option<Card> {
-        self.state
-            .card_database
-            .get()
-            .iter()
-            .find(|c| c.id == id)
-            .cloned()
+        self.state.get_card(id).await
     }
 
     /// Get total number of games played globally
@@ -66,10 +85,197 @@ impl<'a> QueryRoot<'a> {
         self.state.total_games_played.get()
     }
 
-    /// Get active match for a player
-    async fn active_match(&self, owner: String) -> Option<GameState> {
+    /// Get every match a player is currently in
+    async fn active_matches(&self, owner: String) -> Vec<GameState> {
+        let Ok(account_owner) = serde_json::from_str::<AccountOwner>(&format!("\"{}\"", owner))
+        else {
+            return vec![];
+        };
+        let mut matches = vec![];
+        for match_id in self.state.get_player_matches(&account_owner).await {
+            if let Some(game) = self.state.get_match_by_id(match_id).await {
+                matches.push(game);
+            }
+        }
+        matches
+    }
+
+    /// Get a finished match from the history archive
+    async fn match_record(&self, match_id: u64) -> Option<MatchRecord> {
+        self.state.get_match_record(match_id).await
+    }
+
+    /// Get a page of a player's finished matches, newest first.
+    /// Pass the `match_id` of the last record already seen as `after` to
+    /// continue. `opponent` matches anyone who played on another team.
+    async fn match_history(
+        &self,
+        owner: String,
+        first: Option<u32>,
+        after: Option<u64>,
+        result: Option<MatchResult>,
+        mode: Option<MatchMode>,
+        opponent: Option<String>,
+    ) -> Vec<MatchRecord> {
+        let Ok(account_owner) = serde_json::from_str::<AccountOwner>(&format!("\"{}\"", owner))
+        else {
+            return vec![];
+        };
+        let opponent = match opponent {
+            Some(opponent) => {
+                match serde_json::from_str::<AccountOwner>(&format!("\"{}\"", opponent)) {
+                    Ok(opponent) => Some(opponent),
+                    Err(_) => return vec![],
+                }
+            }
+            None => None,
+        };
+        let first = first.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE) as usize;
+
+        // Records are loaded a page at a time until enough pass the filters
+        let mut records = vec![];
+        let mut cursor = after;
+        while records.len() < first {
+            let match_ids = self
+                .state
+                .get_player_history(&account_owner, cursor, first)
+                .await;
+            let Some(last) = match_ids.last() else {
+                break;
+            };
+            cursor = Some(*last);
+            for record in self.state.get_match_records(&match_ids).await {
+                if result.is_some_and(|result| record.result_for(&account_owner) != result)
+                    || mode.is_some_and(|mode| record.mode != mode)
+                    || opponent
+                        .is_some_and(|opponent| !record.is_opponent(&account_owner, &opponent))
+                {
+                    continue;
+                }
+                records.push(record);
+            }
+        }
+        records.truncate(first);
+        records
+    }
+
+    /// Get a player's current or most recent draft run
+    async fn draft_run(&self, owner: String) -> Option<DraftRun> {
+        let account_owner: AccountOwner = serde_json::from_str(&format!("\"{}\"", owner)).ok()?;
+        self.state.get_draft_run(&account_owner).await
+    }
+
+    /// Get a player's sealed pool and the deck built from it
+    async fn sealed_pool(&self, owner: String) -> Option<SealedPool> {
+        let account_owner: AccountOwner = serde_json::from_str(&format!("\"{}\"", owner)).ok()?;
+        self.state.get_sealed_pool(&account_owner).await
+    }
+
+    /// Get a puzzle's starting position and objective
+    async fn puzzle(&self, puzzle_id: u64) -> Option<Puzzle> {
+        self.state.get_puzzle(puzzle_id).await
+    }
+
+    /// Get a best-of-N series and its score
+    async fn series(&self, series_id: u64) -> Option<Series> {
+        self.state.get_series(series_id).await
+    }
+
+    /// Every action `owner` can take right now in a match, from the same
+    /// rules code the contract runs
+    async fn legal_actions(&self, match_id: u64, owner: String) -> Vec<LegalAction> {
+        let Ok(account_owner) = serde_json::from_str::<AccountOwner>(&format!("\"{}\"", owner))
+        else {
+            return vec![];
+        };
+        let Some(game) = self.state.get_match_by_id(match_id).await else {
+            return vec![];
+        };
+        let Some(seat) = game.seat_of(&account_owner) else {
+            return vec![];
+        };
+
+        game.legal_actions(seat).into_iter().map(LegalAction::from).collect()
+    }
+
+    /// A hint for `owner`'s next action in a match, from the AI evaluator.
+    /// Read-only: the AI only plays on a copy of the match.
+    async fn suggested_move(&self, match_id: u64, owner: String) -> Option<MoveSuggestion> {
+        let account_owner: AccountOwner = serde_json::from_str(&format!("\"{}\"", owner)).ok()?;
+        let game = self.state.get_match_by_id(match_id).await?;
+        let seat = game.seat_of(&account_owner)?;
+        let cards = self.state.get_match_cards(&game).await;
+        suggest_move(&game, seat, &cards)
+    }
+
+    /// Get the cards in a player's graveyard in one of their matches
+    async fn graveyard(&self, match_id: u64, owner: String) -> Vec<Card> {
+        self.zone_cards(match_id, owner, Zone::Graveyard).await
+    }
+
+    /// Get the cards a player has exiled in one of their matches
+    async fn exile(&self, match_id: u64, owner: String) -> Vec<Card> {
+        self.zone_cards(match_id, owner, Zone::Exile).await
+    }
+
+    /// Get a page of a match's event log, oldest first, from the match or its
+    /// archived record once finished.
+    /// Pass the `index` of the last event already seen as `after` to continue.
+    async fn match_events(
+        &self,
+        match_id: u64,
+        after: Option<u32>,
+        first: Option<u32>,
+    ) -> Vec<GameEvent> {
+        let game = match self.state.get_match_by_id(match_id).await {
+            Some(game) => game,
+            None => match self.state.get_match_record(match_id).await {
+                Some(record) => record.final_state,
+                None => return vec![],
+            },
+        };
+
+        let start = after.map_or(0, |index| index as usize + 1);
+        let first = first.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE) as usize;
+        game.events.into_iter().skip(start).take(first).collect()
+    }
+
+    /// Get a page of the leaderboard, highest rating first.
+    /// Pass the `rank` of the last entry already seen as `after` to continue.
+    async fn leaderboard(&self, first: Option<u32>, after: Option<u32>) -> Vec<LeaderboardEntry> {
+        let start = after.map_or(0, |rank| rank as usize);
+        let first = first.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE) as usize;
+        self.state.get_leaderboard(start, first).await
+    }
+
+    /// Get a player's position on the leaderboard, 1 being the top
+    async fn player_rank(&self, owner: String) -> Option<u32> {
         let account_owner: AccountOwner = serde_json::from_str(&format!("\"{}\"", owner)).ok()?;
-        self.state.get_match_for_player(&account_owner).await
+        self.state.get_player_rank(&account_owner).await
+    }
+
+    /// Get a page of the admin audit log, oldest first.
+    /// Pass the `index` of the last entry already seen as `after` to continue.
+    async fn audit_log(&self, after: Option<u32>, first: Option<u32>) -> Vec<AuditEntry> {
+        let start = after.map_or(0, |index| index as usize + 1);
+        let first = first.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE) as usize;
+        self.state.get_audit_log(start, first).await
+    }
+
+    /// Accounts allowed to run admin operations
+    async fn admins(&self) -> Vec<AccountOwner> {
+        self.state.admins.get().clone()
+    }
+
+    /// Why a player was banned, or null if they aren't
+    async fn ban_reason(&self, owner: String) -> Option<String> {
+        let account_owner: AccountOwner = serde_json::from_str(&format!("\"{}\"", owner)).ok()?;
+        self.state.get_ban_reason(&account_owner).await
+    }
+
+    /// Cards no longer dealt into new drafts or sealed pools
+    async fn retired_cards(&self) -> Vec<u32> {
+        self.state.get_retired_cards().await
     }
 
     /// Health check endpoint
@@ -78,8 +284,27 @@ impl<'a> QueryRoot<'a> {
     }
 }
 
+impl<'a> QueryRoot<'a> {
+    async fn zone_cards(&self, match_id: u64, owner: String, zone: Zone) -> Vec<Card> {
+        let Ok(account_owner) = serde_json::from_str::<AccountOwner>(&format!("\"{}\"", owner))
+        else {
+            return vec![];
+        };
+        let Some(game) = self.state.get_match_for_player(&account_owner, match_id).await else {
+            return vec![];
+        };
+
+        let Some(seat) = game.seat_of(&account_owner) else {
+            return vec![];
+        };
+        let player = game.player(seat);
+        self.state.get_cards(&player.zone_card_ids(zone)).await
+    }
+}
+
 struct MutationRoot<'a> {
     state: &'a blitz_tactics::state::BlitzTacticsState,
+    runtime: &'a ServiceRuntime<BlitzTacticsService>,
 }
 
 #[Object]
@@ -88,4 +313,49 @@ impl<'a> MutationRoot<'a> {
     async fn placeholder(&self) -> bool {
         true
     }
+
+    /// Schedules `RequestAIMove` for a match whose bot waits for the AI
+    /// oracle, with the oracle's turn if it returns a legal one. Otherwise
+    /// the operation goes without a plan and the built-in AI plays.
+    async fn request_ai_move(&self, match_id: u64) -> bool {
+        let plan = self.oracle_plan(match_id).await;
+        self.runtime
+            .schedule_operation(&Operation::RequestAIMove { match_id, plan });
+        true
+    }
+}
+
+impl<'a> MutationRoot<'a> {
+    /// Asks the AI oracle for the active bot's turn. Returns `None` if no
+    /// oracle is configured, the match doesn't use it, or the oracle replies
+    /// with an error status, an unreadable body or an illegal plan.
+    async fn oracle_plan(&self, match_id: u64) -> Option<Vec<GameAction>> {
+        let url = self.runtime.application_parameters().ai_oracle_url?;
+        let game = self.state.get_match_by_id(match_id).await?;
+        let seat = game.active_seat as usize;
+        if !game.bot_uses_oracle || !game.player(seat).is_bot() {
+            return None;
+        }
+
+        let request = AiMoveRequest {
+            seat: seat as u8,
+            difficulty: game.bot_difficulty.unwrap_or_default(),
+            game: game.clone(),
+            legal_actions: game.legal_actions(seat),
+        };
+        let body = serde_json::to_vec(&request).ok()?;
+        let mut http_request = http::Request::post(url, body);
+        http_request
+            .headers
+            .push(http::Header::new("Content-Type", "application/json"));
+
+        let response = self.runtime.http_request(http_request);
+        if response.status != 200 {
+            return None;
+        }
+        let reply: AiMoveResponse = serde_json::from_slice(&response.body).ok()?;
+        let cards = self.state.get_match_cards(&game).await;
+        validate_plan(&game, seat, &reply.actions, game.turn_timer, &cards).ok()?;
+        Some(reply.actions)
+    }
 }
@@ -0,0 +1,69 @@
+//! Runs bot-vs-bot games off-chain and reports card balance statistics.
+//!
+//! ```text
+//! blitz-tactics-sim [--games N] [--seed S] [--difficulty easy|medium|hard] [--format csv|json] [--out FILE]
+//! ```
+//!
+//! The summary goes to stderr; the CSV or JSON report goes to `--out`, or
+//! stdout when omitted.
+
+use blitz_tactics::{
+    create_starter_deck,
+    simulation::{simulate, SimulationConfig},
+    AiDifficulty,
+};
+
+fn main() {
+    let mut games = 1000;
+    let mut seed = 0;
+    let mut difficulty = AiDifficulty::Medium;
+    let mut json = false;
+    let mut out = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .unwrap_or_else(|| panic!("missing value for {flag}"));
+        match flag.as_str() {
+            "--games" => games = value.parse().expect("--games takes a number"),
+            "--seed" => seed = value.parse().expect("--seed takes a number"),
+            "--difficulty" => {
+                difficulty = match value.as_str() {
+                    "easy" => AiDifficulty::Easy,
+                    "medium" => AiDifficulty::Medium,
+                    "hard" => AiDifficulty::Hard,
+                    other => panic!("unknown difficulty {other}"),
+                }
+            }
+            "--format" => json = value == "json",
+            "--out" => out = Some(value),
+            other => panic!("unknown flag {other}"),
+        }
+    }
+
+    let cards = create_starter_deck();
+    let report = simulate(
+        &cards,
+        &SimulationConfig::mirror(&cards, games, seed, difficulty),
+    );
+    eprintln!(
+        "{} games: first player {} / second player {} / draws {} ({:.1}% first-player wins), {:.1} turns on average",
+        report.games,
+        report.first_player_wins,
+        report.second_player_wins,
+        report.draws,
+        report.first_player_win_rate * 100.0,
+        report.average_turns,
+    );
+
+    let output = if json {
+        serde_json::to_string_pretty(&report).expect("report serializes")
+    } else {
+        report.to_csv()
+    };
+    match out {
+        Some(path) => std::fs::write(path, output).expect("failed to write report"),
+        None => print!("{output}"),
+    }
+}
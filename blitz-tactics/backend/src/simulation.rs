@@ -0,0 +1,218 @@
+use crate::{
+    ai::plan_turn, rng::MatchRng, AiDifficulty, Card, DeckFormat, GameEventKind, GamePhase,
+    GameState, MatchMode, PlayerInGame,
+};
+use linera_sdk::base::{AccountOwner, Timestamp};
+use serde::Serialize;
+
+/// Games still running after this many turns are scored as draws.
+pub const MAX_SIMULATED_TURNS: u8 = 200;
+
+/// Settings for a batch of off-chain bot-vs-bot games.
+#[derive(Debug, Clone)]
+pub struct SimulationConfig {
+    pub games: u32,
+    pub seed: u64,
+    /// Difficulty of the first and second seat.
+    pub difficulties: [AiDifficulty; 2],
+    /// Decks of the first and second seat.
+    pub decks: [Vec<u32>; 2],
+}
+
+impl SimulationConfig {
+    /// Mirror matches between two bots of the same difficulty, each playing
+    /// one copy of every card in `cards`.
+    pub fn mirror(cards: &[Card], games: u32, seed: u64, difficulty: AiDifficulty) -> Self {
+        let deck: Vec<u32> = cards.iter().map(|c| c.id).collect();
+        SimulationConfig {
+            games,
+            seed,
+            difficulties: [difficulty; 2],
+            decks: [deck.clone(), deck],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SimulationReport {
+    pub games: u32,
+    pub first_player_wins: u32,
+    pub second_player_wins: u32,
+    pub draws: u32,
+    pub first_player_win_rate: f64,
+    pub average_turns: f64,
+    pub cards: Vec<CardReport>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CardReport {
+    pub card_id: u32,
+    pub name: String,
+    /// Total times the card was played, across all games.
+    pub times_played: u32,
+    /// Player-games in which the card was played at least once.
+    pub games_played: u32,
+    /// Share of player-games in which the card was played.
+    pub play_rate: f64,
+    pub wins_when_played: u32,
+    pub win_rate_when_played: f64,
+}
+
+/// Runs `config.games` games with the rules engine and the built-in AI.
+pub fn simulate(cards: &[Card], config: &SimulationConfig) -> SimulationReport {
+    let owners = [
+        AccountOwner::Address20([1; 20]),
+        AccountOwner::Address20([2; 20]),
+    ];
+    let mut report = SimulationReport {
+        games: config.games,
+        first_player_wins: 0,
+        second_player_wins: 0,
+        draws: 0,
+        first_player_win_rate: 0.0,
+        average_turns: 0.0,
+        cards: cards
+            .iter()
+            .map(|c| CardReport {
+                card_id: c.id,
+                name: c.name.clone(),
+                times_played: 0,
+                games_played: 0,
+                play_rate: 0.0,
+                wins_when_played: 0,
+                win_rate_when_played: 0.0,
+            })
+            .collect(),
+    };
+    let mut total_turns = 0u64;
+
+    for game_index in 0..config.games {
+        let game = play_game(cards, config, owners, game_index);
+        total_turns += game.current_turn as u64;
+
+        let winners = game.winners();
+        match winners.first() {
+            Some(owner) if *owner == owners[0] => report.first_player_wins += 1,
+            Some(_) => report.second_player_wins += 1,
+            None => report.draws += 1,
+        }
+
+        for owner in owners {
+            let mut played: Vec<u32> = game
+                .events
+                .iter()
+                .filter(|e| e.kind == GameEventKind::CardPlayed && e.player == owner)
+                .filter_map(|e| e.card_id)
+                .collect();
+            for card_id in &played {
+                if let Some(card) = report.cards.iter_mut().find(|c| c.card_id == *card_id) {
+                    card.times_played += 1;
+                }
+            }
+
+            played.sort_unstable();
+            played.dedup();
+            let won = winners.contains(&owner);
+            for card_id in played {
+                if let Some(card) = report.cards.iter_mut().find(|c| c.card_id == card_id) {
+                    card.games_played += 1;
+                    card.wins_when_played += won as u32;
+                }
+            }
+        }
+    }
+
+    let player_games = config.games as f64 * 2.0;
+    report.first_player_win_rate = ratio(report.first_player_wins, config.games as f64);
+    report.average_turns = if config.games == 0 {
+        0.0
+    } else {
+        total_turns as f64 / config.games as f64
+    };
+    for card in &mut report.cards {
+        card.play_rate = ratio(card.games_played, player_games);
+        card.win_rate_when_played = ratio(card.wins_when_played, card.games_played as f64);
+    }
+    report
+}
+
+impl SimulationReport {
+    /// Per-card statistics as CSV, one row per card.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "card_id,name,times_played,games_played,play_rate,wins_when_played,win_rate_when_played\n",
+        );
+        for card in &self.cards {
+            csv.push_str(&format!(
+                "{},\"{}\",{},{},{:.4},{},{:.4}\n",
+                card.card_id,
+                card.name.replace('"', "\"\""),
+                card.times_played,
+                card.games_played,
+                card.play_rate,
+                card.wins_when_played,
+                card.win_rate_when_played,
+            ));
+        }
+        csv
+    }
+}
+
+/// Plays one game to completion, or to `MAX_SIMULATED_TURNS`.
+fn play_game(
+    cards: &[Card],
+    config: &SimulationConfig,
+    owners: [AccountOwner; 2],
+    game_index: u32,
+) -> GameState {
+    let now = Timestamp::from(0);
+    let players = (0..2)
+        .map(|seat| {
+            PlayerInGame::new(
+                owners[seat],
+                seat as u8,
+                seat as u8,
+                config.decks[seat].clone(),
+            )
+        })
+        .collect();
+    let mut game = GameState::new(
+        game_index as u64,
+        None,
+        MatchMode::Duel,
+        DeckFormat::Constructed,
+        players,
+        now,
+    );
+
+    let mut rng = MatchRng::from_parts(&[&config.seed.to_le_bytes(), &game_index.to_le_bytes()]);
+    for seat in 0..game.players.len() {
+        game.shuffle_deck(seat, &mut rng);
+    }
+    game.deal_opening_hands(cards);
+
+    while game.game_phase != GamePhase::Finished && game.current_turn < MAX_SIMULATED_TURNS {
+        let seat = game.active_seat as usize;
+        let owner = game.players[seat].owner;
+        let turn = game.current_turn;
+        let plan = plan_turn(&game, seat, config.difficulties[seat], &mut rng, now, cards);
+        for action in plan {
+            if game.apply_action(owner, &action, now, cards).is_err() {
+                break;
+            }
+        }
+        // Never let a bad plan stall the game
+        if game.game_phase != GamePhase::Finished && game.current_turn == turn {
+            game.end_turn(owner, now, cards).ok();
+        }
+    }
+    game
+}
+
+fn ratio(count: u32, total: f64) -> f64 {
+    if total == 0.0 {
+        0.0
+    } else {
+        count as f64 / total
+    }
+}
@@ -1,36 +1,554 @@
-use crate::{Card, GameState, PlayerStats};
+use crate::{
+    ai::BOT_OWNER,
+    schema::{CardV0, GameStateV0, PlayerStatsV0, SCHEMA_VERSION},
+    AdminAction, AuditEntry, Card, DraftRun, GamePhase, GameState, LeaderboardEntry, MatchRecord,
+    PlayerStats, Puzzle, PuzzleObjective, PveStats, SealedPool, Series, SeriesLength,
+    VersionedGameState, VersionedPlayerStats,
+};
 use linera_sdk::{
-    base::AccountOwner,
-    views::{MapView, RegisterView, RootView, ViewStorageContext},
+    base::{AccountOwner, TimeDelta, Timestamp},
+    views::{
+        CollectionView, LogView, MapView, QueueView, RegisterView, RootView, SetView,
+        ViewStorageContext,
+    },
 };
 use serde::{Deserialize, Serialize};
 
+/// Most matches a player may have in progress at once.
+pub const MAX_ACTIVE_MATCHES: usize = 10;
+/// How long the active player may stay idle before their match counts as
+/// abandoned.
+pub const ABANDON_TIMEOUT: TimeDelta = TimeDelta::from_secs(24 * 60 * 60);
+/// Minimum time between two sweeps of abandoned matches.
+pub const SWEEP_INTERVAL: TimeDelta = TimeDelta::from_secs(10 * 60);
+/// Most abandoned matches a single sweep ends.
+pub const SWEEP_BATCH_SIZE: usize = 10;
+
 #[derive(RootView, SimpleObject)]
 #[view(context = "ViewStorageContext")]
 pub struct BlitzTacticsState {
-    pub players: MapView<AccountOwner, PlayerStats>,
-    pub active_matches: MapView<u64, GameState>,
-    pub player_matches: MapView<AccountOwner, u64>,
-    pub card_database: RegisterView<Vec<Card>>,
+    // Views are keyed by field position. The first six are the original
+    // layout and must stay as they are; new fields go at the end.
+    /// Schema 0 profiles, moved to `players` by the migration.
+    #[graphql(skip)]
+    pub legacy_players: MapView<AccountOwner, PlayerStatsV0>,
+    /// Schema 0 matches, moved to `active_matches`.
+    #[graphql(skip)]
+    pub legacy_matches: MapView<u64, GameStateV0>,
+    /// Schema 0 links from each player to their one match, folded into
+    /// `player_matches`.
+    #[graphql(skip)]
+    pub legacy_player_matches: MapView<AccountOwner, u64>,
+    /// Schema 0 card list, moved to `card_database`.
+    #[graphql(skip)]
+    pub legacy_card_database: RegisterView<Vec<CardV0>>,
     pub total_games_played: RegisterView<u64>,
     pub next_match_id: RegisterView<u64>,
+    /// Finished matches, kept after they leave `active_matches`.
+    pub match_history: MapView<u64, MatchRecord>,
+    /// Each player's finished matches, newest first; see `history_key`.
+    #[graphql(skip)]
+    pub player_history: CollectionView<AccountOwner, SetView<[u8; 8]>>,
+    /// Ids of the matches each player is currently in.
+    pub player_matches: MapView<AccountOwner, Vec<u64>>,
+    /// Cards keyed by id.
+    pub card_database: MapView<u32, Card>,
+    /// Bumped whenever a card is added or changed.
+    pub card_set_version: RegisterView<u32>,
+    pub series: MapView<u64, Series>,
+    pub next_series_id: RegisterView<u64>,
+    pub draft_runs: MapView<AccountOwner, DraftRun>,
+    pub sealed_pools: MapView<AccountOwner, SealedPool>,
+    /// Players with a sealed deck waiting for an opponent.
+    pub sealed_queue: QueueView<AccountOwner>,
+    /// Accounts allowed to run admin operations.
+    pub admins: RegisterView<Vec<AccountOwner>>,
+    pub puzzles: MapView<u64, Puzzle>,
+    pub next_puzzle_id: RegisterView<u64>,
+    /// Layout version of the stored records, behind `SCHEMA_VERSION` while a
+    /// migration is pending.
+    pub schema_version: RegisterView<u32>,
+    /// Read through `get_player_stats`, which upgrades old layouts.
+    #[graphql(skip)]
+    pub players: MapView<AccountOwner, VersionedPlayerStats>,
+    /// Read through `get_match_by_id`, which upgrades old layouts.
+    #[graphql(skip)]
+    pub active_matches: MapView<u64, VersionedGameState>,
+    /// Cards no longer dealt into new drafts or sealed pools.
+    pub retired_cards: SetView<u32>,
+    /// Banned players and the reason given.
+    pub banned_players: MapView<AccountOwner, String>,
+    pub audit_log: LogView<AuditEntry>,
+    /// When abandoned matches were last swept.
+    pub last_sweep: RegisterView<Timestamp>,
+    /// Every profile by rating, highest first; see `ranking_key`. Served by
+    /// the `leaderboard` query.
+    #[graphql(skip)]
+    pub ranking_index: SetView<([u8; 4], AccountOwner)>,
+    /// Profiles the schema 2 migration has added to `ranking_index` so far.
+    pub ranking_index_cursor: RegisterView<u64>,
+    /// Active matches by when their current turn started, oldest first; see
+    /// `turn_timer_key`. Lets a sweep read only the matches past the timeout.
+    #[graphql(skip)]
+    pub turn_timer_index: SetView<([u8; 8], u64)>,
+    /// The turn start each match is filed under in `turn_timer_index`.
+    #[graphql(skip)]
+    pub turn_timers: MapView<u64, Timestamp>,
+    /// Matches the schema 3 migration has added to `turn_timer_index` so far.
+    pub turn_timer_index_cursor: RegisterView<u64>,
+}
+
+/// Ranking index key. Keys are compared as bytes, so the inverted rating is
+/// stored big-endian to put the highest rating first.
+fn ranking_key(stats: &PlayerStats) -> ([u8; 4], AccountOwner) {
+    ((u32::MAX - stats.ranking).to_be_bytes(), stats.owner)
+}
+
+/// Turn timer index key. The time is stored big-endian so keys sort oldest
+/// first.
+fn turn_timer_key(turn_timer: Timestamp, match_id: u64) -> ([u8; 8], u64) {
+    (turn_timer.micros().to_be_bytes(), match_id)
+}
+
+/// Match history key, inverted and big-endian like `ranking_key` so the
+/// newest match comes first.
+fn history_key(match_id: u64) -> [u8; 8] {
+    (u64::MAX - match_id).to_be_bytes()
 }
 
 #[async_trait::async_trait]
 impl BlitzTacticsState {
     pub async fn initialize_card_database(&mut self) -> Result<(), String> {
-        let cards = crate::create_starter_deck();
-        self.card_database.set(cards);
+        for card in crate::create_starter_deck() {
+            let card_id = card.id;
+            self.card_database
+                .insert(&card_id, card)
+                .map_err(|e| format!("Failed to store card: {:?}", e))?;
+        }
+        self.card_set_version.set(1);
+        Ok(())
+    }
+
+    pub async fn get_card(&self, card_id: u32) -> Option<Card> {
+        match self.card_database.get(&card_id).await.ok().flatten() {
+            Some(card) => Some(card),
+            None => self.legacy_card(card_id),
+        }
+    }
+
+    /// Looks up several cards in one read, in order, skipping unknown ids.
+    pub async fn get_cards(&self, card_ids: &[u32]) -> Vec<Card> {
+        self.card_database
+            .multi_get(card_ids)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .zip(card_ids)
+            .filter_map(|(card, card_id)| card.or_else(|| self.legacy_card(*card_id)))
+            .collect()
+    }
+
+    /// A card from the schema 0 list, until the migration moves it.
+    fn legacy_card(&self, card_id: u32) -> Option<Card> {
+        self.legacy_card_database
+            .get()
+            .iter()
+            .find(|card| card.id == card_id)
+            .cloned()
+            .map(Card::from)
+    }
+
+    /// The cards every player brought to a match, which is all the rules
+    /// engine needs to play it.
+    pub async fn get_match_cards(&self, game: &GameState) -> Vec<Card> {
+        let mut ids: Vec<u32> = game.players.iter().flat_map(|p| p.decklist()).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        self.get_cards(&ids).await
+    }
+
+    /// Every card, sorted by id. Reads the whole database, so keep it out of
+    /// per-move paths.
+    pub async fn get_all_cards(&self) -> Vec<Card> {
+        let mut cards: Vec<Card> = self
+            .card_database
+            .index_values()
+            .await
+            .map(|entries| entries.into_iter().map(|(_, card)| card).collect())
+            .unwrap_or_default();
+        for card in self.legacy_card_database.get() {
+            if !cards.iter().any(|c| c.id == card.id) {
+                cards.push(card.clone().into());
+            }
+        }
+        cards.sort_by_key(|card| card.id);
+        cards
+    }
+
+    /// Cards that can still be dealt into drafts and sealed pools, sorted by
+    /// id.
+    pub async fn get_available_cards(&self) -> Vec<Card> {
+        let retired = self.get_retired_cards().await;
+        let mut cards = self.get_all_cards().await;
+        cards.retain(|card| !retired.contains(&card.id));
+        cards
+    }
+
+    pub async fn get_retired_cards(&self) -> Vec<u32> {
+        self.retired_cards.indices().await.unwrap_or_default()
+    }
+
+    pub async fn add_card(&mut self, card: Card) -> Result<(), String> {
+        if self.get_card(card.id).await.is_some() {
+            return Err(format!("Card {} already exists", card.id));
+        }
+        self.put_card(card).await
+    }
+
+    pub async fn update_card(&mut self, card: Card) -> Result<(), String> {
+        if self.get_card(card.id).await.is_none() {
+            return Err(format!("Card {} not found", card.id));
+        }
+        self.put_card(card).await
+    }
+
+    pub async fn retire_card(&mut self, card_id: u32) -> Result<(), String> {
+        if self.get_card(card_id).await.is_none() {
+            return Err(format!("Card {} not found", card_id));
+        }
+        if self.retired_cards.contains(&card_id).await.unwrap_or(false) {
+            return Err(format!("Card {} is already retired", card_id));
+        }
+        self.retired_cards
+            .insert(&card_id)
+            .map_err(|e| e.to_string())?;
+        let version = *self.card_set_version.get();
+        self.card_set_version.set(version + 1);
+        Ok(())
+    }
+
+    /// Adds or replaces a card and bumps the card-set version.
+    pub async fn put_card(&mut self, card: Card) -> Result<(), String> {
+        let card_id = card.id;
+        self.card_database
+            .insert(&card_id, card)
+            .map_err(|e| format!("Failed to store card: {:?}", e))?;
+        let version = *self.card_set_version.get();
+        self.card_set_version.set(version + 1);
         Ok(())
     }
 
     pub async fn get_player_stats(&self, owner: &AccountOwner) -> Option<PlayerStats> {
-        self.players.get(owner).await.ok().flatten()
+        match self.players.get(owner).await.ok().flatten() {
+            Some(stats) => Some(stats.upgrade()),
+            None => self
+                .legacy_players
+                .get(owner)
+                .await
+                .ok()
+                .flatten()
+                .map(PlayerStats::from),
+        }
+    }
+
+    /// Stores a profile in the current layout, retiring any legacy copy, and
+    /// keeps its place in the ranking index.
+    pub async fn put_player_stats(&mut self, stats: PlayerStats) -> Result<(), String> {
+        let owner = stats.owner;
+        if let Some(old) = self.get_player_stats(&owner).await {
+            if old.ranking != stats.ranking {
+                self.ranking_index
+                    .remove(&ranking_key(&old))
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+        self.ranking_index
+            .insert(&ranking_key(&stats))
+            .map_err(|e| e.to_string())?;
+
+        if self.is_migrating() {
+            self.legacy_players
+                .remove(&owner)
+                .map_err(|e| e.to_string())?;
+        }
+        self.players
+            .insert(&owner, stats.into())
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn is_migrating(&self) -> bool {
+        *self.schema_version.get() < SCHEMA_VERSION
+    }
+
+    /// Upgrades up to `limit` records to `SCHEMA_VERSION`: the schema 0 card
+    /// list all at once, then schema 0 profiles, matches and match links,
+    /// then profiles missing from the ranking index, then active matches
+    /// missing from the turn timer index. Once a call finds
+    /// nothing left, the schema version is bumped. Returns the records
+    /// upgraded.
+    ///
+    /// Schema 0 had no admin accounts, so `admins` are installed first when
+    /// none are set.
+    pub async fn migrate(&mut self, limit: u32, admins: &[AccountOwner]) -> Result<u32, String> {
+        let limit = limit as usize;
+        if limit == 0 || !self.is_migrating() {
+            return Ok(0);
+        }
+        if self.admins.get().is_empty() && !admins.is_empty() {
+            self.rotate_admins(admins.to_vec())?;
+        }
+
+        let mut migrated = self.migrate_cards().await?;
+
+        let mut players = vec![];
+        self.legacy_players
+            .for_each_index_value_while(|_, stats| {
+                players.push(PlayerStats::from(stats.into_owned()));
+                Ok(players.len() < limit)
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut games = vec![];
+        let remaining = limit - players.len();
+        if remaining > 0 {
+            self.legacy_matches
+                .for_each_index_value_while(|_, game| {
+                    games.push(GameState::from(game.into_owned()));
+                    Ok(games.len() < remaining)
+                })
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+
+        // Links go last, once every match they can point to has moved
+        let mut links = vec![];
+        let remaining = remaining - games.len();
+        if remaining > 0 {
+            self.legacy_player_matches
+                .for_each_index_value_while(|owner, match_id| {
+                    links.push((owner, *match_id));
+                    Ok(links.len() < remaining)
+                })
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+
+        migrated += (players.len() + games.len() + links.len()) as u32;
+        for stats in players {
+            self.put_player_stats(stats).await?;
+        }
+        for game in games {
+            if game.game_phase != GamePhase::Finished {
+                for player in &game.players {
+                    self.link_player_match(&player.owner, game.match_id).await?;
+                }
+            }
+            self.update_match(game).await?;
+        }
+        for (owner, match_id) in links {
+            let active = self
+                .get_match_by_id(match_id)
+                .await
+                .is_some_and(|game| game.game_phase != GamePhase::Finished);
+            if active {
+                self.link_player_match(&owner, match_id).await?;
+            }
+            self.legacy_player_matches
+                .remove(&owner)
+                .map_err(|e| e.to_string())?;
+        }
+
+        if migrated == 0 {
+            migrated = self.index_rankings(limit).await?;
+        }
+        if migrated == 0 {
+            migrated = self.index_turn_timers(limit).await?;
+        }
+        if migrated == 0 {
+            self.schema_version.set(SCHEMA_VERSION);
+        }
+        Ok(migrated)
+    }
+
+    /// Moves the schema 0 card list into `card_database`, keeping any card
+    /// already stored there. Returns the cards moved.
+    async fn migrate_cards(&mut self) -> Result<u32, String> {
+        let cards = std::mem::take(self.legacy_card_database.get_mut());
+        if cards.is_empty() {
+            return Ok(0);
+        }
+        let count = cards.len() as u32;
+        for card in cards {
+            let card_id = card.id;
+            if self.card_database.get(&card_id).await.ok().flatten().is_none() {
+                self.card_database
+                    .insert(&card_id, card.into())
+                    .map_err(|e| format!("Failed to store card: {:?}", e))?;
+            }
+        }
+        let version = *self.card_set_version.get();
+        self.card_set_version.set(version + 1);
+        Ok(count)
+    }
+
+    /// Adds `match_id` to `owner`'s active matches, once.
+    async fn link_player_match(
+        &mut self,
+        owner: &AccountOwner,
+        match_id: u64,
+    ) -> Result<(), String> {
+        let mut matches = self.get_player_matches(owner).await;
+        if !matches.contains(&match_id) {
+            matches.push(match_id);
+        }
+        self.player_matches
+            .insert(owner, matches)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Adds up to `limit` profiles to the ranking index, resuming after the
+    /// ones earlier calls covered. Adding a profile twice is harmless, so
+    /// profiles created in between at worst cause repeats.
+    async fn index_rankings(&mut self, limit: usize) -> Result<u32, String> {
+        let skip = *self.ranking_index_cursor.get() as usize;
+        let mut position = 0;
+        let mut owners = vec![];
+        self.players
+            .for_each_index_while(|owner| {
+                if position >= skip {
+                    owners.push(owner);
+                }
+                position += 1;
+                Ok(owners.len() < limit)
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+
+        for owner in &owners {
+            if let Some(stats) = self.get_player_stats(owner).await {
+                self.ranking_index
+                    .insert(&ranking_key(&stats))
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+        self.ranking_index_cursor.set((skip + owners.len()) as u64);
+        Ok(owners.len() as u32)
+    }
+
+    /// Adds up to `limit` more active matches to `turn_timer_index`, resuming
+    /// where the previous call stopped. Returns the matches indexed.
+    async fn index_turn_timers(&mut self, limit: usize) -> Result<u32, String> {
+        let skip = *self.turn_timer_index_cursor.get() as usize;
+        let mut position = 0;
+        let mut match_ids = vec![];
+        self.active_matches
+            .for_each_index_while(|match_id| {
+                if position >= skip {
+                    match_ids.push(match_id);
+                }
+                position += 1;
+                Ok(match_ids.len() < limit)
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+
+        for match_id in &match_ids {
+            if let Some(game) = self.get_match_by_id(*match_id).await {
+                self.index_turn_timer(&game).await?;
+            }
+        }
+        self.turn_timer_index_cursor.set((skip + match_ids.len()) as u64);
+        Ok(match_ids.len() as u32)
+    }
+
+    /// Files a match in `turn_timer_index` under its current turn start, or
+    /// takes it out once it is finished.
+    async fn index_turn_timer(&mut self, game: &GameState) -> Result<(), String> {
+        let turn_timer = (game.game_phase != GamePhase::Finished).then_some(game.turn_timer);
+        self.set_turn_timer(game.match_id, turn_timer).await
+    }
+
+    async fn set_turn_timer(
+        &mut self,
+        match_id: u64,
+        turn_timer: Option<Timestamp>,
+    ) -> Result<(), String> {
+        let filed = self.turn_timers.get(&match_id).await.map_err(|e| e.to_string())?;
+        if filed == turn_timer {
+            return Ok(());
+        }
+        if let Some(filed) = filed {
+            self.turn_timer_index
+                .remove(&turn_timer_key(filed, match_id))
+                .map_err(|e| e.to_string())?;
+        }
+        match turn_timer {
+            Some(turn_timer) => {
+                self.turn_timer_index
+                    .insert(&turn_timer_key(turn_timer, match_id))
+                    .map_err(|e| e.to_string())?;
+                self.turn_timers.insert(&match_id, turn_timer)
+            }
+            None => self.turn_timers.remove(&match_id),
+        }
+        .map_err(|e| e.to_string())
+    }
+
+    /// Up to `count` leaderboard entries, best first, skipping the top
+    /// `start`.
+    pub async fn get_leaderboard(&self, start: usize, count: usize) -> Vec<LeaderboardEntry> {
+        let mut owners = vec![];
+        if count > 0 {
+            let mut position = 0;
+            self.ranking_index
+                .for_each_index_while(|(_, owner)| {
+                    if position >= start {
+                        owners.push(owner);
+                    }
+                    position += 1;
+                    Ok(owners.len() < count)
+                })
+                .await
+                .ok();
+        }
+
+        let mut entries = vec![];
+        for (offset, owner) in owners.into_iter().enumerate() {
+            if let Some(stats) = self.get_player_stats(&owner).await {
+                entries.push(LeaderboardEntry {
+                    rank: (start + offset + 1) as u32,
+                    owner,
+                    ranking: stats.ranking,
+                    wins: stats.wins,
+                    losses: stats.losses,
+                    draws: stats.draws,
+                });
+            }
+        }
+        entries
+    }
+
+    /// A player's position on the leaderboard, 1 being the top. Counts the
+    /// players ahead, so cost grows with the rank.
+    pub async fn get_player_rank(&self, owner: &AccountOwner) -> Option<u32> {
+        let key = ranking_key(&self.get_player_stats(owner).await?);
+        let mut position = 0;
+        let mut found = false;
+        self.ranking_index
+            .for_each_index_while(|index| {
+                position += 1;
+                found = index == key;
+                Ok(!found)
+            })
+            .await
+            .ok()?;
+        found.then_some(position)
     }
 
     pub async fn create_player(&mut self, owner: AccountOwner) -> Result<(), String> {
         // Check if player exists
-        if let Some(_) = self.players.get(&owner).await.ok().flatten() {
+        if self.get_player_stats(&owner).await.is_some() {
             return Err("Player already exists".to_string());
         }
 
@@ -43,72 +561,445 @@ impl BlitzTacticsState {
             ranking: 1000,
             total_matches: 0,
             cards_owned: starter_cards,
+            puzzles_solved: vec![],
+            pve: PveStats::default(),
         };
 
-        self.players
-            .insert(&owner, player_stats)
-            .map_err(|e| e.to_string())?;
+        self.put_player_stats(player_stats).await
+    }
+
+    /// Checks a match between `owners` may start: no one plays themselves,
+    /// and every player is unbanned, with a profile and room for another
+    /// match. The bot is exempt.
+    pub async fn validate_new_match(&self, owners: &[AccountOwner]) -> Result<(), String> {
+        for (idx, owner) in owners.iter().enumerate() {
+            if owners[..idx].contains(owner) {
+                return Err("Players can't play against themselves".to_string());
+            }
+            if *owner == BOT_OWNER {
+                continue;
+            }
+            if self.is_banned(owner).await {
+                return Err(format!("{} is banned", owner));
+            }
+            if self.get_player_stats(owner).await.is_none() {
+                return Err(format!(
+                    "{} has no player profile; create one with CreatePlayerProfile",
+                    owner
+                ));
+            }
+            let active = self.get_player_matches(owner).await.len();
+            if active >= MAX_ACTIVE_MATCHES {
+                return Err(format!(
+                    "{} is busy with {} active matches, the most allowed",
+                    owner, active
+                ));
+            }
+        }
         Ok(())
     }
 
-    pub async fn create_match(
-        &mut self,
-        player1: AccountOwner,
-        player2: AccountOwner,
-        mut game_state: GameState,
-    ) -> Result<u64, String> {
+    pub async fn create_match(&mut self, mut game_state: GameState) -> Result<u64, String> {
         // Get next match ID
-        let match_id = self.next_match_id.get();
+        let match_id = *self.next_match_id.get();
+
+        let owners: Vec<AccountOwner> = game_state.players.iter().map(|p| p.owner).collect();
+        // Later games of a series were allowed when it started, so a ban or
+        // a full match list can't leave the series stuck between games
+        let continues_series = match game_state.series_id {
+            Some(series_id) => self
+                .get_series(series_id)
+                .await
+                .is_some_and(|series| series.games_played > 0),
+            None => false,
+        };
+        if !continues_series {
+            self.validate_new_match(&owners).await?;
+        }
         self.next_match_id.set(match_id + 1);
         
         // Set match ID in game state
         game_state.match_id = match_id;
         
+        // Link every player to this match; the bot plays any number at once
+        for owner in owners.iter().filter(|owner| **owner != BOT_OWNER) {
+            self.link_player_match(owner, match_id).await?;
+        }
+
         // Store match by ID
-        self.active_matches
-            .insert(&match_id, game_state)
+        self.update_match(game_state).await?;
+
+        Ok(match_id)
+    }
+
+    pub async fn create_series(
+        &mut self,
+        player1: AccountOwner,
+        player2: AccountOwner,
+        length: SeriesLength,
+    ) -> Result<u64, String> {
+        let series_id = *self.next_series_id.get();
+        self.next_series_id.set(series_id + 1);
+
+        self.series
+            .insert(&series_id, Series::new(series_id, player1, player2, length))
             .map_err(|e| e.to_string())?;
-        
-        // Link both players to this match
-        self.player_matches
-            .insert(&player1, match_id)
+        Ok(series_id)
+    }
+
+    pub async fn get_series(&self, series_id: u64) -> Option<Series> {
+        self.series.get(&series_id).await.ok().flatten()
+    }
+
+    pub async fn update_series(&mut self, series: Series) -> Result<(), String> {
+        let series_id = series.series_id;
+        self.series
+            .insert(&series_id, series)
             .map_err(|e| e.to_string())?;
-        self.player_matches
-            .insert(&player2, match_id)
+        Ok(())
+    }
+
+    pub async fn get_draft_run(&self, owner: &AccountOwner) -> Option<DraftRun> {
+        self.draft_runs.get(owner).await.ok().flatten()
+    }
+
+    pub async fn update_draft_run(&mut self, run: DraftRun) -> Result<(), String> {
+        let owner = run.owner;
+        self.draft_runs
+            .insert(&owner, run)
             .map_err(|e| e.to_string())?;
-        
-        Ok(match_id)
+        Ok(())
     }
 
-    pub async fn get_match_for_player(&self, owner: &AccountOwner) -> Option<GameState> {
-        // Get match ID for this player
-        let match_id = self.player_matches.get(owner).await.ok().flatten()?;
-        
-        // Get match by ID
-        self.active_matches.get(&match_id).await.ok().flatten()
+    pub async fn get_sealed_pool(&self, owner: &AccountOwner) -> Option<SealedPool> {
+        self.sealed_pools.get(owner).await.ok().flatten()
+    }
+
+    pub async fn update_sealed_pool(&mut self, pool: SealedPool) -> Result<(), String> {
+        let owner = pool.owner;
+        self.sealed_pools
+            .insert(&owner, pool)
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Pairs `owner` with the longest-waiting player in the sealed queue, or
+    /// queues them if nobody else is waiting. Waiting players who can no
+    /// longer start a match, e.g. banned or at the match cap, are dropped.
+    pub async fn join_sealed_queue(
+        &mut self,
+        owner: AccountOwner,
+    ) -> Result<Option<AccountOwner>, String> {
+        self.validate_new_match(&[owner]).await?;
+
+        while let Some(waiting) = self.sealed_queue.front().await.map_err(|e| e.to_string())? {
+            if waiting == owner {
+                return Ok(None);
+            }
+            self.sealed_queue.delete_front();
+            if self.validate_new_match(&[waiting, owner]).await.is_ok() {
+                return Ok(Some(waiting));
+            }
+        }
+
+        self.sealed_queue.push_back(owner);
+        Ok(None)
+    }
+
+    pub fn is_admin(&self, owner: &AccountOwner) -> bool {
+        self.admins.get().contains(owner)
+    }
+
+    /// Replaces the admin list, dropping repeats.
+    pub fn rotate_admins(&mut self, mut admins: Vec<AccountOwner>) -> Result<(), String> {
+        if admins.is_empty() {
+            return Err("At least one admin is required".to_string());
+        }
+        let mut seen = vec![];
+        admins.retain(|admin| {
+            let first = !seen.contains(admin);
+            seen.push(*admin);
+            first
+        });
+        self.admins.set(admins);
+        Ok(())
+    }
+
+    pub async fn is_banned(&self, owner: &AccountOwner) -> bool {
+        self.banned_players
+            .contains_key(owner)
+            .await
+            .unwrap_or(false)
+    }
+
+    pub async fn get_ban_reason(&self, owner: &AccountOwner) -> Option<String> {
+        self.banned_players.get(owner).await.ok().flatten()
+    }
+
+    pub async fn ban_player(&mut self, owner: AccountOwner, reason: String) -> Result<(), String> {
+        if self.is_admin(&owner) {
+            return Err("Admins can't be banned; rotate them out first".to_string());
+        }
+        if self.is_banned(&owner).await {
+            return Err(format!("{} is already banned", owner));
+        }
+        self.banned_players
+            .insert(&owner, reason)
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn unban_player(&mut self, owner: &AccountOwner) -> Result<(), String> {
+        if !self.is_banned(owner).await {
+            return Err(format!("{} is not banned", owner));
+        }
+        self.banned_players.remove(owner).map_err(|e| e.to_string())
+    }
+
+    /// Appends an admin operation to the audit log.
+    pub fn record_admin_action(
+        &mut self,
+        admin: AccountOwner,
+        timestamp: Timestamp,
+        action: AdminAction,
+        detail: String,
+    ) {
+        let index = self.audit_log.count() as u32;
+        self.audit_log.push(AuditEntry {
+            index,
+            admin,
+            timestamp,
+            action,
+            detail,
+        });
+    }
+
+    /// Reads up to `first` audit entries, oldest first, starting at `start`.
+    pub async fn get_audit_log(&self, start: usize, first: usize) -> Vec<AuditEntry> {
+        let end = self.audit_log.count().min(start.saturating_add(first));
+        if start >= end {
+            return vec![];
+        }
+        self.audit_log.read(start..end).await.unwrap_or_default()
+    }
+
+    pub async fn add_puzzle(
+        &mut self,
+        name: String,
+        description: String,
+        start: GameState,
+        objective: PuzzleObjective,
+    ) -> Result<u64, String> {
+        let puzzle_id = *self.next_puzzle_id.get();
+        let puzzle = Puzzle::new(puzzle_id, name, description, start, objective)?;
+        self.next_puzzle_id.set(puzzle_id + 1);
+
+        self.puzzles
+            .insert(&puzzle_id, puzzle)
+            .map_err(|e| e.to_string())?;
+        Ok(puzzle_id)
+    }
+
+    pub async fn get_puzzle(&self, puzzle_id: u64) -> Option<Puzzle> {
+        self.puzzles.get(&puzzle_id).await.ok().flatten()
+    }
+
+    /// Records a solved puzzle on the player's profile. Solving the same
+    /// puzzle again is a no-op.
+    pub async fn record_puzzle_solved(
+        &mut self,
+        owner: &AccountOwner,
+        puzzle_id: u64,
+    ) -> Result<(), String> {
+        let mut stats = self
+            .get_player_stats(owner)
+            .await
+            .ok_or("Player profile not found")?;
+        if !stats.puzzles_solved.contains(&puzzle_id) {
+            stats.puzzles_solved.push(puzzle_id);
+            self.put_player_stats(stats).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn get_player_matches(&self, owner: &AccountOwner) -> Vec<u64> {
+        let mut matches = self
+            .player_matches
+            .get(owner)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        if self.is_migrating() {
+            if let Ok(Some(match_id)) = self.legacy_player_matches.get(owner).await {
+                if !matches.contains(&match_id) {
+                    matches.push(match_id);
+                }
+            }
+        }
+        matches
+    }
+
+    /// Gets one of `owner`'s active matches; `None` if they aren't in it.
+    pub async fn get_match_for_player(
+        &self,
+        owner: &AccountOwner,
+        match_id: u64,
+    ) -> Option<GameState> {
+        if !self.get_player_matches(owner).await.contains(&match_id) {
+            return None;
+        }
+        self.get_match_by_id(match_id).await
+    }
+
+    /// Starts a sweep at `now`, unless the last one was under
+    /// `SWEEP_INTERVAL` ago.
+    pub fn start_sweep(&mut self, now: Timestamp) -> Result<(), String> {
+        let next_sweep = self.last_sweep.get().saturating_add(SWEEP_INTERVAL);
+        if now < next_sweep {
+            return Err(format!("Matches were swept recently; try again after {}", next_sweep));
+        }
+        self.last_sweep.set(now);
+        Ok(())
+    }
+
+    /// Up to `limit` matches whose active player has been idle past
+    /// `ABANDON_TIMEOUT`. Only the expired start of `turn_timer_index` is
+    /// read.
+    pub async fn find_abandoned_matches(&self, now: Timestamp, limit: usize) -> Vec<GameState> {
+        if limit == 0 {
+            return vec![];
+        }
+        let mut match_ids = vec![];
+        self.turn_timer_index
+            .for_each_index_while(|(turn_timer, match_id)| {
+                let turn_timer = Timestamp::from(u64::from_be_bytes(turn_timer));
+                if turn_timer.saturating_add(ABANDON_TIMEOUT) > now {
+                    return Ok(false);
+                }
+                match_ids.push(match_id);
+                Ok(match_ids.len() < limit)
+            })
+            .await
+            .ok();
+
+        let mut games = vec![];
+        for match_id in match_ids {
+            if let Some(game) = self.get_match_by_id(match_id).await {
+                if game.is_abandoned(now, ABANDON_TIMEOUT) {
+                    games.push(game);
+                }
+            }
+        }
+        games
     }
 
     pub async fn get_match_by_id(&self, match_id: u64) -> Option<GameState> {
-        self.active_matches.get(&match_id).await.ok().flatten()
+        match self.active_matches.get(&match_id).await.ok().flatten() {
+            Some(game) => Some(game.upgrade()),
+            None => self
+                .legacy_matches
+                .get(&match_id)
+                .await
+                .ok()
+                .flatten()
+                .map(GameState::from),
+        }
+    }
+
+    pub async fn get_match_record(&self, match_id: u64) -> Option<MatchRecord> {
+        self.match_history.get(&match_id).await.ok().flatten()
+    }
+
+    /// Loads finished matches in the order given, skipping unknown ids.
+    pub async fn get_match_records(&self, match_ids: &[u64]) -> Vec<MatchRecord> {
+        self.match_history
+            .multi_get(match_ids)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    /// Up to `first` of a player's finished match ids, newest first, starting
+    /// after the match `after` when given.
+    pub async fn get_player_history(
+        &self,
+        owner: &AccountOwner,
+        after: Option<u64>,
+        first: usize,
+    ) -> Vec<u64> {
+        let Ok(Some(history)) = self.player_history.try_load_entry(owner).await else {
+            return vec![];
+        };
+        let start = after.map(history_key);
+        let mut match_ids = vec![];
+        history
+            .for_each_index_while(|key| {
+                if start.map_or(true, |start| key > start) {
+                    match_ids.push(u64::MAX - u64::from_be_bytes(key));
+                }
+                Ok(match_ids.len() < first)
+            })
+            .await
+            .ok();
+        match_ids
     }
 
     pub async fn update_match(&mut self, game_state: GameState) -> Result<(), String> {
         let match_id = game_state.match_id;
+        if self.is_migrating() {
+            self.legacy_matches
+                .remove(&match_id)
+                .map_err(|e| e.to_string())?;
+        }
+        self.index_turn_timer(&game_state).await?;
         self.active_matches
-            .insert(&match_id, game_state)
+            .insert(&match_id, game_state.into())
             .map_err(|e| e.to_string())?;
         Ok(())
     }
 
-    pub async fn end_match(&mut self, match_id: u64) -> Result<(), String> {
+    /// Closes a match, moving its final state into the history archive.
+    pub async fn end_match(&mut self, match_id: u64, ended_at: Timestamp) -> Result<(), String> {
         // Get match to find players
-        if let Some(game) = self.active_matches.get(&match_id).await.ok().flatten() {
-            // Remove player references
-            self.player_matches.remove(&game.player1.owner).map_err(|e| e.to_string())?;
-            self.player_matches.remove(&game.player2.owner).map_err(|e| e.to_string())?;
+        if let Some(game) = self.get_match_by_id(match_id).await {
+            // Remove player references and index the match in their history
+            for player in game.players.iter().filter(|p| !p.is_bot()) {
+                let mut matches = self.get_player_matches(&player.owner).await;
+                matches.retain(|id| *id != match_id);
+                if matches.is_empty() {
+                    self.player_matches.remove(&player.owner)
+                } else {
+                    self.player_matches.insert(&player.owner, matches)
+                }
+                .map_err(|e| e.to_string())?;
+                if self.is_migrating() {
+                    self.legacy_player_matches
+                        .remove(&player.owner)
+                        .map_err(|e| e.to_string())?;
+                }
+
+                self.player_history
+                    .load_entry_mut(&player.owner)
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .insert(&history_key(match_id))
+                    .map_err(|e| e.to_string())?;
+            }
+
+            self.match_history
+                .insert(&match_id, MatchRecord::new(game, ended_at))
+                .map_err(|e| e.to_string())?;
         }
         
         // Remove match
+        if self.is_migrating() {
+            self.legacy_matches
+                .remove(&match_id)
+                .map_err(|e| e.to_string())?;
+        }
+        self.set_turn_timer(match_id, None).await?;
         self.active_matches
             .remove(&match_id)
             .map_err(|e| e.to_string())?;
@@ -116,44 +1007,91 @@ impl BlitzTacticsState {
     }
 
     pub async fn update_player_win(&mut self, owner: &AccountOwner) -> Result<(), String> {
-        if let Some(mut stats) = self.players.get(owner).await.ok().flatten() {
+        if let Some(mut stats) = self.get_player_stats(owner).await {
             stats.wins += 1;
             stats.total_matches += 1;
             stats.ranking = stats.ranking.saturating_add(25);
-            self.players
-                .insert(owner, stats)
-                .map_err(|e| e.to_string())?;
+            self.put_player_stats(stats).await?;
         }
         Ok(())
     }
 
     pub async fn update_player_loss(&mut self, owner: &AccountOwner) -> Result<(), String> {
-        if let Some(mut stats) = self.players.get(owner).await.ok().flatten() {
+        if let Some(mut stats) = self.get_player_stats(owner).await {
             stats.losses += 1;
             stats.total_matches += 1;
             stats.ranking = stats.ranking.saturating_sub(15);
-            self.players
-                .insert(owner, stats)
-                .map_err(|e| e.to_string())?;
+            self.put_player_stats(stats).await?;
         }
         Ok(())
     }
 
-    pub async fn add_card_to_player(
+    pub async fn update_player_draw(&mut self, owner: &AccountOwner) -> Result<(), String> {
+        if let Some(mut stats) = self.get_player_stats(owner).await {
+            stats.draws += 1;
+            stats.total_matches += 1;
+            self.put_player_stats(stats).await?;
+        }
+        Ok(())
+    }
+
+    /// Records a result against the bot; `won` is `None` for a draw.
+    pub async fn update_player_pve(
         &mut self,
         owner: &AccountOwner,
-        card_id: u32,
+        won: Option<bool>,
     ) -> Result<(), String> {
-        if let Some(mut stats) = self.players.get(owner).await.ok().flatten() {
-            if !stats.cards_owned.contains(&card_id) {
-                stats.cards_owned.push(card_id);
-                self.players
-                    .insert(owner, stats)
-                    .map_err(|e| e.to_string())?;
+        if let Some(mut stats) = self.get_player_stats(owner).await {
+            match won {
+                Some(true) => stats.pve.wins += 1,
+                Some(false) => stats.pve.losses += 1,
+                None => stats.pve.draws += 1,
             }
+            self.put_player_stats(stats).await?;
         }
         Ok(())
     }
+
+    /// Records a multiplayer result. First place gains 25 and last place
+    /// loses 15, as in a duel, with the places in between spread evenly.
+    pub async fn update_player_placement(
+        &mut self,
+        owner: &AccountOwner,
+        place: u32,
+        places: u32,
+    ) -> Result<(), String> {
+        if let Some(mut stats) = self.get_player_stats(owner).await {
+            if place == 1 {
+                stats.wins += 1;
+            } else {
+                stats.losses += 1;
+            }
+            stats.total_matches += 1;
+
+            let delta = if places > 1 {
+                25 - ((place - 1) * 40 / (places - 1)) as i32
+            } else {
+                25
+            };
+            stats.ranking = stats.ranking.saturating_add_signed(delta);
+            self.put_player_stats(stats).await?;
+        }
+        Ok(())
+    }
+
+    /// Adds one copy of a card to the player's collection.
+    pub async fn add_card_to_player(
+        &mut self,
+        owner: &AccountOwner,
+        card_id: u32,
+    ) -> Result<(), String> {
+        let mut stats = self
+            .get_player_stats(owner)
+            .await
+            .ok_or("Player profile not found")?;
+        stats.cards_owned.push(card_id);
+        self.put_player_stats(stats).await
+    }
 }
 
 use async_graphql::SimpleObject;